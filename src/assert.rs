@@ -1,3 +1,14 @@
+/// How to reproduce a failing randomized test.
+#[macro_export]
+macro_rules! seed_note {
+    () => {
+        format!(
+            "random seed: {0} (rerun with SEED={0})",
+            $crate::random::seed()
+        )
+    };
+}
+
 /// Asserts that two matrices are the same, with an relative tolerance.
 #[macro_export]
 macro_rules! assert_eq_mat {
@@ -9,15 +20,17 @@ macro_rules! assert_eq_mat {
             (left, right) => {
                 assert!(
                     !right.contains_nan(),
-                    "Received matrix contains NaN:\nright: {right}",
+                    "Received matrix contains NaN:\nright: {right}\n{}",
+                    $crate::seed_note!(),
                 );
                 assert!(
                     !left.contains_nan(),
-                    "Received matrix contains NaN:\nleft: {left}",
+                    "Received matrix contains NaN:\nleft: {left}\n{}",
+                    $crate::seed_note!(),
                 );
                 assert!(
                     right.eq(left, $tol),
-                    "Matrices do not match:\nleft: {left:?}\nright: {right:?}\ndiff: {:?}", left - right,
+                    "Matrices do not match:\nleft: {left:?}\nright: {right:?}\ndiff: {:?}\n{}", left - right, $crate::seed_note!(),
                 );
             }
         }
@@ -35,15 +48,17 @@ macro_rules! assert_ne_mat {
             (left, right) => {
                 assert!(
                     !right.contains_nan(),
-                    "Received matrix contains NaN:\nright: {right}",
+                    "Received matrix contains NaN:\nright: {right}\n{}",
+                    $crate::seed_note!(),
                 );
                 assert!(
                     !left.contains_nan(),
-                    "Received matrix contains NaN:\nleft: {left}",
+                    "Received matrix contains NaN:\nleft: {left}\n{}",
+                    $crate::seed_note!(),
                 );
                 assert!(
                     !right.eq(left, $tol),
-                    "Matrices are too close:\nleft: {left:?}\nright: {right:?}\ndiff: {:?}", left - right,
+                    "Matrices are too close:\nleft: {left:?}\nright: {right:?}\ndiff: {:?}\n{}", left - right, $crate::seed_note!(),
                 );
             }
         }
@@ -58,9 +73,10 @@ macro_rules! assert_eq_tol {
         assert!(!$r.is_nan(), "Right value is NaN");
         assert!(
             $l.rel_diff($r) < $tol,
-            "Scalars do not match:\nleft: {}\nright: {}",
+            "Scalars do not match:\nleft: {}\nright: {}\n{}",
             $l,
-            $r
+            $r,
+            $crate::seed_note!()
         )
     };
 }
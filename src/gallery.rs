@@ -0,0 +1,317 @@
+// Classic test matrices, with closed-form inverses and eigenvalues where
+// they are known, for checking the algorithms in `na`.
+
+use crate::prelude::*;
+
+use std::f64::consts::PI;
+
+#[allow(unused)]
+fn demo() {
+    let x = mat![1; 2; 3];
+    hilbert::<3>();
+    hilbert_inverse::<3>();
+    vandermonde::<3, 3>(&x);
+    toeplitz(&x, &x.t());
+    hankel(&x, &x.t());
+    circulant(&x);
+    pascal::<3>();
+    pascal_inverse::<3>();
+    wilkinson::<3>();
+    frank::<3>();
+    kahan::<3>(1.);
+    laplacian_1d::<3>();
+    laplacian_1d_sparse::<3>();
+    laplacian_1d_inverse::<3>();
+    laplacian_1d_eigenvalues::<3>();
+    laplacian_2d::<2, 4>();
+    laplacian_2d_eigenvalues::<2, 4>();
+}
+
+/// Binomial coefficient n choose k, as a float.
+fn binomial(n: usize, k: usize) -> R {
+    if k > n {
+        return 0.;
+    }
+    let k = k.min(n - k);
+    (1..=k).fold(1., |c, i| c * (n + 1 - i) as R / i as R).round()
+}
+
+/// Hilbert matrix, H(i, j) = 1/(i + j - 1). Notoriously ill-conditioned.
+pub fn hilbert<const N: usize>() -> Mat<N, N> {
+    Mat::from_fn(|i, j| ((i + j - 1) as R).recip())
+}
+
+/// Exact inverse of the Hilbert matrix, whose entries are integers.
+pub fn hilbert_inverse<const N: usize>() -> Mat<N, N> {
+    Mat::from_fn(|i, j| {
+        let sign = if (i + j) % 2 == 0 { 1. } else { -1. };
+        sign * (i + j - 1) as R
+            * binomial(N + i - 1, N - j)
+            * binomial(N + j - 1, N - i)
+            * binomial(i + j - 2, i - 1).powi(2)
+    })
+}
+
+/// Vandermonde matrix of the points `x`, V(i, j) = x_i^(N - j), so that
+/// Vp evaluates the polynomial p (highest degree first, as in `horners`)
+/// at each point.
+pub fn vandermonde<const M: usize, const N: usize>(x: &Mat<M, 1>) -> Mat<M, N> {
+    Mat::from_fn(|i, j| x[i].powi((N - j) as i32))
+}
+
+/// Toeplitz matrix with first column `c` and first row `r`: constant
+/// along each diagonal. Where they overlap, `c` wins, as in MATLAB.
+pub fn toeplitz<const M: usize, const N: usize>(
+    c: &Mat<M, 1>,
+    r: &Mat<1, N>,
+) -> Mat<M, N> {
+    Mat::from_fn(|i, j| if i >= j { c[i - j + 1] } else { r[(1, j - i + 1)] })
+}
+
+/// Hankel matrix with first column `c` and last row `r`: constant along
+/// each anti-diagonal. Where they overlap, `c` wins, as in MATLAB.
+pub fn hankel<const M: usize, const N: usize>(
+    c: &Mat<M, 1>,
+    r: &Mat<1, N>,
+) -> Mat<M, N> {
+    Mat::from_fn(|i, j| match i + j - 1 {
+        k if k <= M => c[k],
+        k => r[(1, k - M + 1)],
+    })
+}
+
+/// Circulant matrix with first column `c`: each column is the one
+/// before it shifted down by one, wrapping around.
+pub fn circulant<const N: usize>(c: &Mat<N, 1>) -> Mat<N, N> {
+    Mat::from_fn(|i, j| c[(i + N - j) % N + 1])
+}
+
+/// Symmetric Pascal matrix, P(i, j) = binomial(i + j - 2, j - 1). It
+/// has determinant 1 and an integer inverse.
+pub fn pascal<const N: usize>() -> Mat<N, N> {
+    Mat::from_fn(|i, j| binomial(i + j - 2, j - 1))
+}
+
+/// Exact inverse of the Pascal matrix. P = LLᵀ with L(i, j) =
+/// binomial(i - 1, j - 1), and L⁻¹ is L with alternating signs.
+pub fn pascal_inverse<const N: usize>() -> Mat<N, N> {
+    let L_inv = Mat::<N, N>::from_fn(|i, j| {
+        let sign = if (i + j) % 2 == 0 { 1. } else { -1. };
+        sign * binomial(i - 1, j - 1)
+    });
+    L_inv.t() * L_inv
+}
+
+/// Wilkinson's W⁺ matrix: tridiagonal with ones off the diagonal and
+/// |(N - 1)/2 - (i - 1)| on it. Its largest eigenvalues come in nearly
+/// equal pairs.
+pub fn wilkinson<const N: usize>() -> Mat<N, N> {
+    let m = (N as R - 1.) / 2.;
+    Mat::from_fn(|i, j| match i.abs_diff(j) {
+        0 => (m - (i - 1) as R).abs(),
+        1 => 1.,
+        _ => 0.,
+    })
+}
+
+/// Frank matrix: upper Hessenberg with F(i, j) = N + 1 - max(i, j).
+/// It has determinant 1, and its small eigenvalues are very sensitive
+/// to perturbations.
+pub fn frank<const N: usize>() -> Mat<N, N> {
+    Mat::from_fn(|i, j| match i <= j + 1 {
+        true => (N + 1 - i.max(j)) as R,
+        false => 0.,
+    })
+}
+
+/// Kahan matrix: upper triangular diag(1, s, ..., s^(N-1)) times the
+/// unit upper triangular matrix with -c above the diagonal, where
+/// s = sin θ and c = cos θ. QR with column pivoting fails to reveal its
+/// rank.
+pub fn kahan<const N: usize>(theta: R) -> UpperTriangular<N> {
+    let (s, c) = theta.sin_cos();
+    let K = Mat::from_fn(|i, j| match i.cmp(&j) {
+        std::cmp::Ordering::Greater => 0.,
+        std::cmp::Ordering::Equal => s.powi(i as i32 - 1),
+        std::cmp::Ordering::Less => -c * s.powi(i as i32 - 1),
+    });
+    K.into()
+}
+
+/// 1D Laplacian, the tridiagonal [-1, 2, -1] second difference matrix
+/// on N interior grid points (with grid spacing 1).
+pub fn laplacian_1d<const N: usize>() -> Mat<N, N> {
+    Mat::from_fn(|i, j| match i.abs_diff(j) {
+        0 => 2.,
+        1 => -1.,
+        _ => 0.,
+    })
+}
+
+/// 1D Laplacian, in sparse storage.
+pub fn laplacian_1d_sparse<const N: usize>() -> Sparse<N, N> {
+    let mut entries = vec![];
+    for i in 1..=N {
+        entries.push((i, i, 2.));
+        if i > 1 {
+            entries.extend([(i, i - 1, -1.), (i - 1, i, -1.)]);
+        }
+    }
+    Sparse::from_triplets(&entries)
+}
+
+/// Exact inverse of the 1D Laplacian, min(i, j)(N + 1 - max(i, j))/(N + 1).
+pub fn laplacian_1d_inverse<const N: usize>() -> Mat<N, N> {
+    let n = (N + 1) as R;
+    Mat::from_fn(|i, j| (i.min(j) as R) * (n - i.max(j) as R) / n)
+}
+
+/// Eigenvalues of the 1D Laplacian in ascending order,
+/// 4 sin²(kπ / 2(N + 1)).
+pub fn laplacian_1d_eigenvalues<const N: usize>() -> Mat<N, 1> {
+    Mat::from_fn(|k, _| {
+        let s = (k as R * PI / (2 * (N + 1)) as R).sin();
+        4. * s * s
+    })
+}
+
+/// 2D Laplacian, the 5-point stencil on a K×K grid of interior points
+/// numbered row by row (with grid spacing 1). N MUST be K².
+pub fn laplacian_2d<const K: usize, const N: usize>() -> Mat<N, N> {
+    laplacian_2d_sparse::<K, N>().to_mat()
+}
+
+/// 2D Laplacian, in sparse storage. N MUST be K².
+pub fn laplacian_2d_sparse<const K: usize, const N: usize>() -> Sparse<N, N> {
+    const { assert!(N == K * K, "N must be K²") };
+    let mut entries = vec![];
+    for x in 1..=K {
+        for y in 1..=K {
+            let i = (x - 1) * K + y;
+            entries.push((i, i, 4.));
+            if y > 1 {
+                entries.extend([(i, i - 1, -1.), (i - 1, i, -1.)]);
+            }
+            if x > 1 {
+                entries.extend([(i, i - K, -1.), (i - K, i, -1.)]);
+            }
+        }
+    }
+    Sparse::from_triplets(&entries)
+}
+
+/// Eigenvalues of the 2D Laplacian in ascending order: the sums of
+/// pairs of eigenvalues of the 1D Laplacian on K points.
+pub fn laplacian_2d_eigenvalues<const K: usize, const N: usize>() -> Mat<N, 1> {
+    const { assert!(N == K * K, "N must be K²") };
+    let lambda = laplacian_1d_eigenvalues::<K>();
+    let mut sums: Vec<R> = (1..=K)
+        .flat_map(|a| (1..=K).map(move |b| (a, b)))
+        .map(|(a, b)| lambda[a] + lambda[b])
+        .collect();
+    sums.sort_by(R::total_cmp);
+    Mat::from_fn(|k, _| sums[k - 1])
+}
+
+#[cfg(test)]
+fn eigenvalues<const N: usize>(A: &Mat<N, N>) -> Mat<N, 1> {
+    Symmetric::from_mat(A, 1e-12).unwrap().eigen().unwrap().0
+}
+
+#[test]
+fn hilbert_test() {
+    let H = hilbert::<6>();
+    assert!(max_abs_diff(&(&H * &hilbert_inverse::<6>()), &Mat::eye()) < 1e-8);
+    assert_eq!(
+        hilbert_inverse::<3>().data,
+        mat![9, -36, 30; -36, 192, -180; 30, -180, 180].data
+    );
+}
+
+#[test]
+fn vandermonde_test() {
+    let x = mat![-2; 0; 1; 2; 3];
+    let p = mat![2; 0; -4; 3];
+    let V = vandermonde::<5, 4>(&x);
+    let y = &V * &p;
+    for i in 1..=5 {
+        assert_eq!(y[i], crate::na::horners(&p.as_vec(), x[i]));
+    }
+}
+
+#[test]
+fn toeplitz_hankel_circulant_test() {
+    let T = toeplitz(&mat![1; 2; 3], &mat![9, 5, 6, 7]);
+    assert_eq!(T.data, mat![1, 5, 6, 7; 2, 1, 5, 6; 3, 2, 1, 5].data);
+    let H = hankel(&mat![1; 2; 3], &mat![9, 5, 6, 7]);
+    assert_eq!(H.data, mat![1, 2, 3, 5; 2, 3, 5, 6; 3, 5, 6, 7].data);
+    let C = circulant(&mat![1; 2; 3]);
+    assert_eq!(C.data, mat![1, 3, 2; 2, 1, 3; 3, 2, 1].data);
+    // Circulant matrices commute.
+    let D = circulant(&mat![-1; 4; 0.5]);
+    assert!(max_abs_diff(&(&C * &D), &(&D * &C)) < 1e-12);
+}
+
+#[test]
+fn pascal_test() {
+    let P = pascal::<4>();
+    assert_eq!(
+        P.data,
+        mat![1, 1, 1, 1; 1, 2, 3, 4; 1, 3, 6, 10; 1, 4, 10, 20].data
+    );
+    assert_eq!((&P * &pascal_inverse::<4>()).data, Mat::<4, 4>::eye().data);
+    assert_eq!(
+        P.cholesky().as_mat().data,
+        mat![1, 0, 0, 0; 1, 1, 0, 0; 1, 2, 1, 0; 1, 3, 3, 1].data
+    );
+}
+
+#[test]
+fn wilkinson_frank_kahan_test() {
+    let lambda = eigenvalues(&wilkinson::<21>());
+    // The two largest eigenvalues agree to about 14 digits.
+    assert!((lambda[21] - lambda[20]).abs() < 1e-12);
+    assert!((lambda[21] - 10.746194182903393).abs() < 1e-12);
+
+    let (_, U) = frank::<8>().lu();
+    assert!((U.det() - 1.).abs() < 1e-9);
+    assert!(frank::<8>()[(4, 2)] == 0. && frank::<8>()[(3, 2)] == 6.);
+
+    let theta: R = 1.2;
+    let K = kahan::<5>(theta);
+    assert_eq_tol!(K.det(), theta.sin().powi(10), 1e-12);
+    assert_eq!(K[(2, 4)], -theta.cos() * theta.sin());
+}
+
+#[test]
+fn laplacian_1d_test() {
+    const N: usize = 9;
+    let L = laplacian_1d::<N>();
+    assert_eq!(laplacian_1d_sparse::<N>().to_mat().data, L.data);
+    assert!(
+        max_abs_diff(&(&L * &laplacian_1d_inverse::<N>()), &Mat::eye()) < 1e-12
+    );
+    assert!(
+        max_abs_diff(&eigenvalues(&L), &laplacian_1d_eigenvalues::<N>())
+            < 1e-12
+    );
+}
+
+#[test]
+fn laplacian_2d_test() {
+    let L = laplacian_2d::<4, 16>();
+    assert_eq!(laplacian_2d_sparse::<4, 16>().nnz(), 16 + 2 * 2 * 4 * 3);
+    assert_eq!(
+        (L[(6, 6)], L[(6, 5)], L[(6, 2)], L[(5, 4)]),
+        (4., -1., -1., 0.)
+    );
+    assert!(
+        max_abs_diff(&eigenvalues(&L), &laplacian_2d_eigenvalues::<4, 16>())
+            < 1e-12
+    );
+
+    // Big enough that only the sparse form is practical.
+    let L = laplacian_2d_sparse::<100, 10000>();
+    let x = Mat::<10000, 1>::from_fn(|i, _| i as R);
+    assert_eq!((&L * &x)[5050], 0.);
+}
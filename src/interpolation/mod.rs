@@ -5,12 +5,12 @@ mod traits;
 pub use lagrange::*;
 pub use newton::*;
 
-use crate::prelude::*;
+use traits::Interpolator;
 
 #[allow(unused)]
 fn demo() {
-    let xs = Mat::from([[-2., 0., 1., 2.]]).t();
-    let ys = Mat::from([[-5., 3., 1., 11.]]).t();
-    NewtonInterpolation::new(&xs, &ys);
-    LagrangeInterpolation::new(&xs, &ys);
+    let xs = mat![-2; 0; 1; 2];
+    let ys = mat![-5; 3; 1; 11];
+    NewtonInterpolation::new(&xs, &ys).estimate(0.);
+    LagrangeInterpolation::new(&xs, &ys).estimate(0.);
 }
@@ -45,8 +45,8 @@ impl<const N: usize> Interpolator<N> for NewtonInterpolation<N> {
 fn newton_interpolation_test() {
     use crate::na::horners;
 
-    let xs = Mat::from([[-2., 0., 1., 2.]]).t();
-    let ys = Mat::from([[-5., 3., 1., 11.]]).t();
+    let xs = mat![-2; 0; 1; 2];
+    let ys = mat![-5; 3; 1; 11];
     let lg = NewtonInterpolation::new(&xs, &ys);
     let pl = vec![2., 0., -4., 3.];
     let pl = |x| horners(&pl, x);
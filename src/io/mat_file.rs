@@ -0,0 +1,433 @@
+// MATLAB Level 5 MAT-files, uncompressed (as written by `save -v6`).
+// https://www.mathworks.com/help/pdf_doc/matlab/matfile_format.pdf
+//
+// Only real, full, 2-dimensional numeric arrays are read. Other
+// variables (strings, cells, structs, sparse, complex) are skipped.
+
+use crate::prelude::*;
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+// Data types of data elements.
+const MI_INT8: u32 = 1;
+const MI_UINT8: u32 = 2;
+const MI_INT16: u32 = 3;
+const MI_UINT16: u32 = 4;
+const MI_INT32: u32 = 5;
+const MI_UINT32: u32 = 6;
+const MI_SINGLE: u32 = 7;
+const MI_DOUBLE: u32 = 9;
+const MI_INT64: u32 = 12;
+const MI_UINT64: u32 = 13;
+const MI_MATRIX: u32 = 14;
+const MI_COMPRESSED: u32 = 15;
+
+// Array classes that hold plain numbers.
+const MX_DOUBLE_CLASS: u8 = 6;
+const MX_UINT64_CLASS: u8 = 15;
+
+/// Array flag for arrays that have an imaginary part.
+const COMPLEX_FLAG: u8 = 0x08;
+
+const HEADER_LEN: usize = 128;
+
+/// A named real matrix, stored column-major like `Mat::data`.
+#[derive(Clone, Debug)]
+struct Variable {
+    name: String,
+    dims: (usize, usize),
+    data: Vec<R>,
+}
+
+/// Contents of a MAT-file: real double matrices, by name.
+#[derive(Clone, Debug, Default)]
+pub struct MatFile {
+    vars: Vec<Variable>,
+}
+
+/// Reads numbers out of a byte buffer in the file's byte order.
+struct Bytes<'a> {
+    buf: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Bytes<'a> {
+    fn take<const K: usize>(&mut self) -> Result<[u8; K]> {
+        if self.buf.len() < K {
+            return Err(Error::Parse("unexpected end of MAT-file".into()));
+        }
+        let (head, tail) = self.buf.split_at(K);
+        self.buf = tail;
+        let mut bytes: [u8; K] = head.try_into().unwrap();
+        if self.big_endian {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn skip(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.buf.len() < n {
+            return Err(Error::Parse("unexpected end of MAT-file".into()));
+        }
+        let (head, tail) = self.buf.split_at(n);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    /// Reads a data element: returns its type and its data. Handles
+    /// both the full 8-byte tag and the "small data element" format,
+    /// where up to 4 bytes of data are packed into the tag.
+    fn element(&mut self) -> Result<(u32, Bytes<'a>)> {
+        let tag = self.u32()?;
+        let (ty, data) = if tag >> 16 != 0 {
+            let n = (tag >> 16) as usize;
+            if n > 4 {
+                let msg = format!("small data element of {n} bytes");
+                return Err(Error::Parse(msg));
+            }
+            let data = self.skip(4)?;
+            (tag & 0xffff, &data[..n])
+        } else {
+            let n = self.u32()? as usize;
+            let data = self.skip(n)?;
+            self.skip(padding(n).min(self.buf.len()))?;
+            (tag, data)
+        };
+        Ok((ty, Bytes { buf: data, big_endian: self.big_endian }))
+    }
+
+    /// Reads the whole buffer as numbers of the given data type.
+    fn numbers(&mut self, ty: u32) -> Result<Vec<R>> {
+        let mut v = vec![];
+        while !self.buf.is_empty() {
+            v.push(match ty {
+                MI_INT8 => i8::from_le_bytes(self.take()?) as R,
+                MI_UINT8 => u8::from_le_bytes(self.take()?) as R,
+                MI_INT16 => i16::from_le_bytes(self.take()?) as R,
+                MI_UINT16 => u16::from_le_bytes(self.take()?) as R,
+                MI_INT32 => i32::from_le_bytes(self.take()?) as R,
+                MI_UINT32 => u32::from_le_bytes(self.take()?) as R,
+                MI_SINGLE => f32::from_le_bytes(self.take()?) as R,
+                MI_DOUBLE => f64::from_le_bytes(self.take()?),
+                MI_INT64 => i64::from_le_bytes(self.take()?) as R,
+                MI_UINT64 => u64::from_le_bytes(self.take()?) as R,
+                _ => {
+                    let msg = format!("unsupported data type {ty}");
+                    return Err(Error::Parse(msg));
+                }
+            });
+        }
+        Ok(v)
+    }
+}
+
+/// Bytes needed to pad `n` bytes to an 8-byte boundary.
+fn padding(n: usize) -> usize {
+    (8 - n % 8) % 8
+}
+
+/// Parses the contents of a miMATRIX element. Returns None for arrays
+/// that are not real numeric matrices.
+fn parse_matrix(mut el: Bytes) -> Result<Option<Variable>> {
+    if el.buf.is_empty() {
+        return Ok(None); // empty array.
+    }
+    let (_, flags) = el.element()?;
+    let flags = flags.buf;
+    if flags.len() < 4 {
+        return Err(Error::Parse("malformed array flags".into()));
+    }
+    // The class and the flags are the two lowest bytes of a u32.
+    let (class, flags) = match el.big_endian {
+        false => (flags[0], flags[1]),
+        true => (flags[3], flags[2]),
+    };
+    if !(MX_DOUBLE_CLASS..=MX_UINT64_CLASS).contains(&class)
+        || flags & COMPLEX_FLAG != 0
+    {
+        return Ok(None);
+    }
+
+    let (ty, mut dims) = el.element()?;
+    let dims = dims.numbers(ty)?;
+    let (name_ty, name) = el.element()?;
+    if name_ty != MI_INT8 {
+        return Err(Error::Parse("malformed array name".into()));
+    }
+    let name = String::from_utf8_lossy(name.buf).into_owned();
+    let (ty, mut real) = el.element()?;
+    let data = real.numbers(ty)?;
+
+    // Trailing singleton dimensions are allowed: 3×2×1 is 3×2.
+    let dims: Vec<usize> = dims.iter().map(|&d| d as usize).collect();
+    if dims.len() < 2 || dims[2..].iter().any(|&d| d != 1) {
+        let msg = format!("`{name}` has more than 2 dimensions");
+        return Err(Error::Parse(msg));
+    }
+    if data.len() != dims[0] * dims[1] {
+        let msg = format!("`{name}` has the wrong number of elements");
+        return Err(Error::Parse(msg));
+    }
+    Ok(Some(Variable { name, dims: (dims[0], dims[1]), data }))
+}
+
+fn write_tag<W: Write>(w: &mut W, ty: u32, n: usize) -> Result<()> {
+    w.write_all(&ty.to_le_bytes())?;
+    w.write_all(&(n as u32).to_le_bytes())?;
+    Ok(())
+}
+
+fn write_padding<W: Write>(w: &mut W, n: usize) -> Result<()> {
+    w.write_all(&[0; 8][..padding(n)])?;
+    Ok(())
+}
+
+impl MatFile {
+    /// New MAT-file with no variables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a MAT-file.
+    pub fn read<Rd: Read>(mut reader: Rd) -> Result<Self> {
+        let mut buf = vec![];
+        reader.read_to_end(&mut buf)?;
+        if buf.len() < HEADER_LEN {
+            return Err(Error::Parse("MAT-file header is too short".into()));
+        }
+        // Written as 'MI' by the writer's native byte order.
+        let big_endian = match &buf[126..128] {
+            b"IM" => false,
+            b"MI" => true,
+            _ => return Err(Error::Parse("not a Level 5 MAT-file".into())),
+        };
+        let mut bytes = Bytes { buf: &buf[HEADER_LEN..], big_endian };
+
+        let mut vars = vec![];
+        while !bytes.buf.is_empty() {
+            match bytes.element()? {
+                (MI_MATRIX, el) => vars.extend(parse_matrix(el)?),
+                (MI_COMPRESSED, _) => {
+                    let msg = "compressed MAT-files are not supported: \
+                               save with `-v6`";
+                    return Err(Error::Parse(msg.into()));
+                }
+                _ => {} // not a variable.
+            }
+        }
+        Ok(Self { vars })
+    }
+
+    /// Read a MAT-file from disk.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Serialize to the MAT-file format, little-endian.
+    pub fn write<W: Write>(&self, mut w: W) -> Result<()> {
+        let mut text = format!(
+            "MATLAB 5.0 MAT-file, Created by: numerical-methods {}",
+            env!("CARGO_PKG_VERSION")
+        )
+        .into_bytes();
+        text.resize(116, b' ');
+        w.write_all(&text)?;
+        w.write_all(&[0; 8])?; // no subsystem data.
+        w.write_all(&0x0100u16.to_le_bytes())?;
+        w.write_all(b"IM")?;
+
+        for var in &self.vars {
+            let name = var.name.as_bytes();
+            let name_len = name.len() + padding(name.len());
+            let data_len = 8 * var.data.len();
+            // flags, dims, name and real part, with their tags.
+            let len = (8 + 8) + (8 + 8) + (8 + name_len) + (8 + data_len);
+            write_tag(&mut w, MI_MATRIX, len)?;
+
+            write_tag(&mut w, MI_UINT32, 8)?;
+            w.write_all(&(MX_DOUBLE_CLASS as u32).to_le_bytes())?;
+            w.write_all(&0u32.to_le_bytes())?;
+
+            write_tag(&mut w, MI_INT32, 8)?;
+            w.write_all(&(var.dims.0 as i32).to_le_bytes())?;
+            w.write_all(&(var.dims.1 as i32).to_le_bytes())?;
+
+            write_tag(&mut w, MI_INT8, name.len())?;
+            w.write_all(name)?;
+            write_padding(&mut w, name.len())?;
+
+            write_tag(&mut w, MI_DOUBLE, data_len)?;
+            for v in &var.data {
+                w.write_all(&v.to_le_bytes())?;
+            }
+        }
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Write the MAT-file to disk.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+
+    /// Names of the variables, in file order.
+    pub fn names(&self) -> Vec<&str> {
+        self.vars.iter().map(|v| v.name.as_str()).collect()
+    }
+
+    /// Get the variable `name`, which must be an M×N matrix.
+    pub fn get<const M: usize, const N: usize>(
+        &self,
+        name: &str,
+    ) -> Result<Mat<M, N>> {
+        let var = self.vars.iter().find(|v| v.name == name);
+        let var = var.ok_or_else(|| Error::MissingVariable(name.into()))?;
+        if var.dims != (M, N) {
+            let (expected, found) = ((M, N), var.dims);
+            return Err(Error::DimensionMismatch { expected, found });
+        }
+        let mut A = Mat::zero();
+        A.data.iter_mut().flatten().zip(&var.data).for_each(|(a, v)| *a = *v);
+        Ok(A)
+    }
+
+    /// Set the variable `name`, replacing any variable of that name.
+    pub fn insert<const M: usize, const N: usize>(
+        &mut self,
+        name: &str,
+        A: &Mat<M, N>,
+    ) {
+        let data = A.data.iter().flatten().copied().collect();
+        let var = Variable { name: name.into(), dims: (M, N), data };
+        match self.vars.iter_mut().find(|v| v.name == name) {
+            Some(v) => *v = var,
+            None => self.vars.push(var),
+        }
+    }
+}
+
+#[test]
+fn mat_file_roundtrip_test() {
+    let A = Mat::<4, 3>::rand();
+    let x = Mat::<5, 1>::rand();
+    let mut f = MatFile::new();
+    f.insert("A", &A);
+    f.insert("x_long_name", &x);
+    f.insert("A", &(2. * &A)); // replaces
+
+    let mut buf = vec![];
+    f.write(&mut buf).unwrap();
+    assert_eq!(buf.len() % 8, 0);
+
+    let f = MatFile::read(&buf[..]).unwrap();
+    assert_eq!(f.names(), ["A", "x_long_name"]);
+    assert_eq!(f.get::<4, 3>("A").unwrap().data, (2. * A).data);
+    assert_eq!(f.get::<5, 1>("x_long_name").unwrap().data, x.data);
+    assert!(matches!(
+        f.get::<3, 4>("A"),
+        Err(Error::DimensionMismatch { expected: (3, 4), found: (4, 3) })
+    ));
+    assert!(matches!(f.get::<4, 3>("B"), Err(Error::MissingVariable(_))));
+}
+
+/// Files as MATLAB writes them: names packed into small data elements,
+/// integer-valued doubles stored as miUINT8, and either byte order.
+#[test]
+fn mat_file_matlab_test() {
+    fn el(out: &mut Vec<u8>, be: bool, ty: u32, data: &[u8]) {
+        let u32b = |v: u32| if be { v.to_be_bytes() } else { v.to_le_bytes() };
+        if data.len() <= 4 {
+            out.extend(u32b((data.len() as u32) << 16 | ty));
+            out.extend(data);
+            out.extend(&[0; 4][data.len()..]);
+        } else {
+            out.extend(u32b(ty));
+            out.extend(u32b(data.len() as u32));
+            out.extend(data);
+            out.extend(&[0; 8][..padding(data.len())]);
+        }
+    }
+    for be in [false, true] {
+        let u32b = |v: u32| if be { v.to_be_bytes() } else { v.to_le_bytes() };
+        let i32b = |v: i32| if be { v.to_be_bytes() } else { v.to_le_bytes() };
+
+        // A = [1 2 3; 4 5 6], stored column-major as uint8.
+        let mut m = vec![];
+        el(
+            &mut m,
+            be,
+            MI_UINT32,
+            &[u32b(MX_DOUBLE_CLASS as u32), u32b(0)].concat(),
+        );
+        el(&mut m, be, MI_INT32, &[i32b(2), i32b(3)].concat());
+        el(&mut m, be, MI_INT8, b"A");
+        el(&mut m, be, MI_UINT8, &[1, 4, 2, 5, 3, 6]);
+        // a char array, which should be skipped.
+        let mut c = vec![];
+        el(&mut c, be, MI_UINT32, &[u32b(4), u32b(0)].concat());
+        el(&mut c, be, MI_INT32, &[i32b(1), i32b(2)].concat());
+        el(&mut c, be, MI_INT8, b"s");
+        el(&mut c, be, MI_UINT16, &[0, b'h', 0, b'i']);
+
+        let mut file = vec![b' '; 116];
+        file.extend([0; 8]);
+        file.extend(if be { [1, 0] } else { [0, 1] });
+        file.extend(if be { b"MI" } else { b"IM" });
+        el(&mut file, be, MI_MATRIX, &c);
+        el(&mut file, be, MI_MATRIX, &m);
+
+        let f = MatFile::read(&file[..]).unwrap();
+        assert_eq!(f.names(), ["A"]);
+        let A = f.get::<2, 3>("A").unwrap();
+        assert_eq!(A.data, Mat::from([[1., 2., 3.], [4., 5., 6.]]).data);
+    }
+}
+
+#[test]
+fn mat_file_error_test() {
+    assert!(matches!(MatFile::read(&[0u8; 10][..]), Err(Error::Parse(_))));
+    let mut file = vec![b' '; 116];
+    file.extend([0; 8]);
+    file.extend([0, 1]);
+    file.extend(b"IM");
+    let header = file.clone();
+    file.extend(MI_COMPRESSED.to_le_bytes());
+    file.extend(0u32.to_le_bytes());
+    assert!(matches!(MatFile::read(&file[..]), Err(Error::Parse(_))));
+
+    // A small data element claiming more than the 4 bytes it can hold.
+    let mut file = header;
+    file.extend((7u32 << 16 | MI_INT8).to_le_bytes());
+    file.extend(b"abcd");
+    assert!(matches!(MatFile::read(&file[..]), Err(Error::Parse(_))));
+
+    // Truncated anywhere, a file is an error, not a panic.
+    let mut f = MatFile::new();
+    f.insert("A", &Mat::<2, 3>::rand());
+    let mut buf = vec![];
+    f.write(&mut buf).unwrap();
+    for len in HEADER_LEN + 1..buf.len() {
+        let read = MatFile::read(&buf[..len]);
+        assert!(matches!(read, Err(Error::Parse(_))), "{len}");
+    }
+}
+
+#[test]
+fn mat_file_save_test() {
+    let path = std::env::temp_dir().join("mat_file_save_test.mat");
+    let mut f = MatFile::new();
+    f.insert("Q", &Mat::<3, 3>::rand());
+    f.save(&path).unwrap();
+    let g = MatFile::open(&path).unwrap();
+    assert_eq!(
+        g.get::<3, 3>("Q").unwrap().data,
+        f.get::<3, 3>("Q").unwrap().data
+    );
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,337 @@
+// Matrix Market (.mtx) exchange format.
+// https://math.nist.gov/MatrixMarket/formats.html
+
+use crate::prelude::*;
+
+use std::io::{BufRead, Write};
+
+/// How the entries are laid out in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Every entry, column by column.
+    Array,
+    /// Only the nonzero entries, as (row, col, value) triplets.
+    Coordinate,
+}
+
+/// Which part of the matrix is stored in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Every entry.
+    General,
+    /// Lower triangle, including the diagonal. A = Aᵀ.
+    Symmetric,
+    /// Strictly lower triangle; the diagonal is zero. A = -Aᵀ.
+    SkewSymmetric,
+}
+
+impl Symmetry {
+    /// True if the entry at (i, j) is stored in the file.
+    fn stores(&self, i: usize, j: usize) -> bool {
+        match self {
+            Symmetry::General => true,
+            Symmetry::Symmetric => i >= j,
+            Symmetry::SkewSymmetric => i > j,
+        }
+    }
+
+    /// Value of the entry at (j, i), given the one at (i, j).
+    fn mirror(&self, v: R) -> R {
+        match self {
+            Symmetry::SkewSymmetric => -v,
+            _ => v,
+        }
+    }
+}
+
+fn parse_err(line: usize, msg: impl std::fmt::Display) -> Error {
+    Error::Parse(format!("line {line}: {msg}"))
+}
+
+/// Parses the header line, e.g.
+/// `%%MatrixMarket matrix coordinate real symmetric`.
+fn parse_header(line: &str) -> Result<(Format, Symmetry)> {
+    let words: Vec<String> =
+        line.split_whitespace().map(|w| w.to_lowercase()).collect();
+    let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+    let (format, field, symmetry) = match words[..] {
+        ["%%matrixmarket", "matrix", format, field, symmetry] => {
+            (format, field, symmetry)
+        }
+        _ => return Err(parse_err(1, "expected a %%MatrixMarket header")),
+    };
+    let format = match format {
+        "array" => Format::Array,
+        "coordinate" => Format::Coordinate,
+        _ => return Err(parse_err(1, format!("unknown format `{format}`"))),
+    };
+    if !matches!(field, "real" | "double" | "integer") {
+        return Err(parse_err(1, format!("unsupported field `{field}`")));
+    }
+    let symmetry = match symmetry {
+        "general" => Symmetry::General,
+        "symmetric" => Symmetry::Symmetric,
+        "skew-symmetric" => Symmetry::SkewSymmetric,
+        _ => return Err(parse_err(1, format!("unsupported `{symmetry}`"))),
+    };
+    Ok((format, symmetry))
+}
+
+fn parse_num<T: std::str::FromStr>(line: usize, word: &str) -> Result<T> {
+    word.parse().map_err(|_| parse_err(line, format!("bad number `{word}`")))
+}
+
+/// Reads a real matrix in Matrix Market format. The size in the file
+/// must match M×N.
+pub fn read<const M: usize, const N: usize, B: BufRead>(
+    reader: B,
+) -> Result<Mat<M, N>> {
+    let mut lines = reader.lines().enumerate().map(|(i, l)| (i + 1, l));
+    let header = match lines.next() {
+        Some((_, line)) => line?,
+        None => return Err(parse_err(1, "empty file")),
+    };
+    let (format, symmetry) = parse_header(&header)?;
+
+    // Data lines: everything but comments and blank lines, split into
+    // whitespace-separated words.
+    let mut data = lines.filter_map(|(n, line)| match line {
+        Ok(line) if line.starts_with('%') || line.trim().is_empty() => None,
+        Ok(line) => Some(Ok((
+            n,
+            line.split_whitespace().map(String::from).collect::<Vec<_>>(),
+        ))),
+        Err(e) => Some(Err(Error::from(e))),
+    });
+
+    let (n, size) = match data.next() {
+        Some(line) => line?,
+        None => return Err(parse_err(2, "missing size line")),
+    };
+    let expected_len = if format == Format::Array { 2 } else { 3 };
+    if size.len() != expected_len {
+        return Err(parse_err(n, "malformed size line"));
+    }
+    let (rows, cols) = (parse_num(n, &size[0])?, parse_num(n, &size[1])?);
+    if (rows, cols) != (M, N) {
+        return Err(Error::DimensionMismatch {
+            expected: (M, N),
+            found: (rows, cols),
+        });
+    }
+    if symmetry != Symmetry::General && M != N {
+        return Err(parse_err(n, "symmetric matrices must be square"));
+    }
+
+    let mut A = Mat::<M, N>::zero();
+    let mut set = |i: usize, j: usize, v: R| {
+        A[(i, j)] = v;
+        if i != j && symmetry != Symmetry::General {
+            A[(j, i)] = symmetry.mirror(v);
+        }
+    };
+
+    match format {
+        Format::Array => {
+            let stored = (1..=N)
+                .flat_map(|j| (1..=M).map(move |i| (i, j)))
+                .filter(|&(i, j)| symmetry.stores(i, j));
+            for (i, j) in stored {
+                let (n, words) = match data.next() {
+                    Some(line) => line?,
+                    None => return Err(parse_err(n, "too few entries")),
+                };
+                if words.len() != 1 {
+                    return Err(parse_err(n, "expected a single value"));
+                }
+                set(i, j, parse_num(n, &words[0])?);
+            }
+        }
+        Format::Coordinate => {
+            let nnz: usize = parse_num(n, &size[2])?;
+            for _ in 0..nnz {
+                let (n, words) = match data.next() {
+                    Some(line) => line?,
+                    None => return Err(parse_err(n, "too few entries")),
+                };
+                if words.len() != 3 {
+                    return Err(parse_err(n, "expected `row col value`"));
+                }
+                let i: usize = parse_num(n, &words[0])?;
+                let j: usize = parse_num(n, &words[1])?;
+                if !(1..=M).contains(&i) || !(1..=N).contains(&j) {
+                    return Err(parse_err(
+                        n,
+                        format!("({i}, {j}) is out of range"),
+                    ));
+                }
+                if !symmetry.stores(i, j) {
+                    return Err(parse_err(
+                        n,
+                        format!("({i}, {j}) is above the diagonal"),
+                    ));
+                }
+                set(i, j, parse_num(n, &words[2])?);
+            }
+        }
+    }
+
+    if let Some(line) = data.next() {
+        return Err(parse_err(line?.0, "too many entries"));
+    }
+    Ok(A)
+}
+
+/// Writes a real matrix in Matrix Market format. With a symmetric or
+/// skew-symmetric `symmetry`, only the lower triangle of `A` is written,
+/// and `A` must be square.
+pub fn write<const M: usize, const N: usize, W: Write>(
+    A: &Mat<M, N>,
+    mut writer: W,
+    format: Format,
+    symmetry: Symmetry,
+) -> Result<()> {
+    if symmetry != Symmetry::General && M != N {
+        return Err(Error::DimensionMismatch {
+            expected: (N, N),
+            found: (M, N),
+        });
+    }
+    let format_name = match format {
+        Format::Array => "array",
+        Format::Coordinate => "coordinate",
+    };
+    let symmetry_name = match symmetry {
+        Symmetry::General => "general",
+        Symmetry::Symmetric => "symmetric",
+        Symmetry::SkewSymmetric => "skew-symmetric",
+    };
+    writeln!(
+        writer,
+        "%%MatrixMarket matrix {format_name} real {symmetry_name}"
+    )?;
+
+    let stored: Vec<(usize, usize)> = (1..=N)
+        .flat_map(|j| (1..=M).map(move |i| (i, j)))
+        .filter(|&(i, j)| symmetry.stores(i, j))
+        .collect();
+    match format {
+        Format::Array => {
+            writeln!(writer, "{M} {N}")?;
+            for (i, j) in stored {
+                writeln!(writer, "{:e}", A[(i, j)])?;
+            }
+        }
+        Format::Coordinate => {
+            let nonzero: Vec<_> =
+                stored.into_iter().filter(|&(i, j)| A[(i, j)] != 0.).collect();
+            writeln!(writer, "{M} {N} {}", nonzero.len())?;
+            for (i, j) in nonzero {
+                writeln!(writer, "{i} {j} {:e}", A[(i, j)])?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[test]
+fn matrix_market_roundtrip_test() {
+    let mut A = Mat::<4, 3>::rand();
+    A[(2, 2)] = 0.;
+    for format in [Format::Array, Format::Coordinate] {
+        let mut buf = vec![];
+        write(&A, &mut buf, format, Symmetry::General).unwrap();
+        let B: Mat<4, 3> = read(&buf[..]).unwrap();
+        assert_eq!(A.data, B.data);
+    }
+
+    let S = Mat::<4, 4>::symmetric();
+    let K = Mat::<4, 4>::rand();
+    let K = &K - K.t();
+    for format in [Format::Array, Format::Coordinate] {
+        let mut buf = vec![];
+        write(&S, &mut buf, format, Symmetry::Symmetric).unwrap();
+        let B: Mat<4, 4> = read(&buf[..]).unwrap();
+        assert_eq!(S.data, B.data);
+
+        let mut buf = vec![];
+        write(&K, &mut buf, format, Symmetry::SkewSymmetric).unwrap();
+        let B: Mat<4, 4> = read(&buf[..]).unwrap();
+        assert_eq!(K.data, B.data);
+    }
+}
+
+#[test]
+fn matrix_market_read_test() {
+    let file = "\
+%%MatrixMarket matrix coordinate real symmetric
+% a comment
+3 3 4
+1 1 2.0
+2 1 -1
+3 2 -1
+
+3 3 2e0
+";
+    let A: Mat<3, 3> = read(file.as_bytes()).unwrap();
+    let expected = Mat::from([[2., -1., 0.], [-1., 0., -1.], [0., -1., 2.]]);
+    assert_eq!(A.data, expected.data);
+
+    let file = "%%MatrixMarket matrix array real skew-symmetric\n2 2\n5\n";
+    let A: Mat<2, 2> = read(file.as_bytes()).unwrap();
+    assert_eq!(A.data, Mat::from([[0., -5.], [5., 0.]]).data);
+}
+
+#[test]
+fn matrix_market_error_test() {
+    fn read_err<const M: usize, const N: usize>(file: &str) -> Error {
+        read::<M, N, _>(file.as_bytes()).unwrap_err()
+    }
+    let general = "%%MatrixMarket matrix array real general\n";
+    assert!(matches!(
+        read_err::<3, 2>(&format!("{general}2 3\n1\n2\n3\n4\n5\n6\n")),
+        Error::DimensionMismatch { expected: (3, 2), found: (2, 3) }
+    ));
+    assert!(matches!(
+        read_err::<1, 2>(&format!("{general}1 2\n1\n")),
+        Error::Parse(_)
+    ));
+    assert!(matches!(
+        read_err::<1, 1>(&format!("{general}1 1\n1\n2\n")),
+        Error::Parse(_)
+    ));
+    assert!(matches!(
+        read_err::<1, 1>(&format!("{general}1 1\nx\n")),
+        Error::Parse(_)
+    ));
+    assert!(matches!(
+        read_err::<1, 1>("%%MatrixMarket matrix array complex general\n"),
+        Error::Parse(_)
+    ));
+    assert!(matches!(
+        read_err::<2, 2>(
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n3 1 1\n"
+        ),
+        Error::Parse(_)
+    ));
+    let A = Mat::<3, 2>::rand();
+    assert!(matches!(
+        write(&A, vec![], Format::Coordinate, Symmetry::Symmetric),
+        Err(Error::DimensionMismatch { expected: (2, 2), found: (3, 2) })
+    ));
+}
+
+#[test]
+fn matrix_market_file_test() {
+    let path = std::env::temp_dir().join("matrix_market_file_test.mtx");
+    let A = Mat::<5, 2>::rand();
+    A.write_matrix_market(&path, Format::Array, Symmetry::General).unwrap();
+    let B = Mat::<5, 2>::read_matrix_market(&path).unwrap();
+    assert_eq!(A.data, B.data);
+    assert!(matches!(
+        Mat::<2, 5>::read_matrix_market(&path),
+        Err(Error::DimensionMismatch { .. })
+    ));
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,30 @@
+// Reading and writing matrices in the file formats of other tools.
+
+pub mod mat_file;
+pub mod matrix_market;
+pub mod npy;
+pub mod npz;
+pub mod text;
+
+use crate::prelude::*;
+use mat_file::MatFile;
+use npz::Npz;
+
+#[allow(unused)]
+fn demo() -> Result<()> {
+    let mut f = MatFile::open("householder.mat")?;
+    let Q = f.get::<3, 3>("Q")?;
+    f.insert("Q", &Q);
+    f.save("householder.mat")?;
+    f.names();
+    MatFile::new().write(std::io::sink())?;
+
+    let mut f = Npz::open("arrays.npz")?;
+    let A = f.get::<3, 3>("A")?;
+    f.insert("A", &A);
+    f.save("arrays.npz")?;
+    f.names();
+    Npz::new().write(std::io::sink())?;
+    npy::write(&A, std::io::sink(), npy::Order::C)?;
+    npy::read::<3, 3, _>(std::io::empty()).map(drop)
+}
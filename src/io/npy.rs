@@ -0,0 +1,217 @@
+// NumPy .npy arrays of float64.
+// https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html
+
+use crate::prelude::*;
+
+use std::io::{Read, Write};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Memory layout of the array data in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Row-major, NumPy's default.
+    C,
+    /// Column-major, the same as `Mat::data`.
+    Fortran,
+}
+
+/// Finds the value of `'key':` in the header dict, as written by NumPy:
+/// a quoted string, a parenthesized tuple, or a bare word.
+fn dict_value<'a>(header: &'a str, key: &str) -> Result<&'a str> {
+    let missing = || Error::Parse(format!("npy header has no `{key}`"));
+    let start = header.find(&format!("'{key}'")).ok_or_else(missing)?;
+    let rest = header[start + key.len() + 2..].trim_start();
+    let rest = rest.strip_prefix(':').ok_or_else(missing)?.trim_start();
+    let end = match rest.chars().next() {
+        Some('(') => rest.find(')').map(|i| i + 1),
+        Some(q @ ('\'' | '"')) => rest[1..].find(q).map(|i| i + 2),
+        _ => rest.find([',', '}']),
+    };
+    Ok(&rest[..end.ok_or_else(missing)?])
+}
+
+/// Reads a float64 array of shape (M, N) from a .npy file. A 1-d array
+/// of length K can be read as a K×1 or a 1×K matrix.
+pub fn read<const M: usize, const N: usize, Rd: Read>(
+    mut reader: Rd,
+) -> Result<Mat<M, N>> {
+    let mut preamble = [0; 8];
+    reader.read_exact(&mut preamble)?;
+    if &preamble[..6] != MAGIC {
+        return Err(Error::Parse("not a .npy file".into()));
+    }
+    let header_len = match preamble[6] {
+        1 => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        v => return Err(Error::Parse(format!("unknown .npy version {v}"))),
+    };
+    let mut header = vec![0; header_len];
+    reader.read_exact(&mut header)?;
+    let header = String::from_utf8_lossy(&header);
+
+    let big_endian = match dict_value(&header, "descr")? {
+        "'<f8'" | "'=f8'" => false,
+        "'>f8'" => true,
+        descr => {
+            let msg = format!("unsupported dtype {descr}: expected float64");
+            return Err(Error::Parse(msg));
+        }
+    };
+    let order = match dict_value(&header, "fortran_order")? {
+        "True" => Order::Fortran,
+        "False" => Order::C,
+        v => return Err(Error::Parse(format!("bad fortran_order `{v}`"))),
+    };
+    let shape = dict_value(&header, "shape")?;
+    let shape: Vec<usize> = shape
+        .trim_matches(['(', ')'])
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| {
+            d.parse().map_err(|_| Error::Parse(format!("bad shape {shape}")))
+        })
+        .collect::<Result<_>>()?;
+    let found = match shape[..] {
+        [k] if (M, N) == (1, k) => (1, k),
+        [k] => (k, 1),
+        [m, n] => (m, n),
+        _ => {
+            let msg = format!("array has {} dimensions", shape.len());
+            return Err(Error::Parse(msg));
+        }
+    };
+    if found != (M, N) {
+        return Err(Error::DimensionMismatch { expected: (M, N), found });
+    }
+
+    let mut A = Mat::<M, N>::zero();
+    let mut read_value = || -> Result<R> {
+        let mut v = [0; 8];
+        reader.read_exact(&mut v)?;
+        Ok(if big_endian { R::from_be_bytes(v) } else { R::from_le_bytes(v) })
+    };
+    match order {
+        Order::Fortran => {
+            for v in A.data.iter_mut().flatten() {
+                *v = read_value()?;
+            }
+        }
+        Order::C => {
+            for i in 1..=M {
+                for j in 1..=N {
+                    A[(i, j)] = read_value()?;
+                }
+            }
+        }
+    }
+    Ok(A)
+}
+
+/// Writes a matrix as a little-endian float64 .npy file of shape (M, N).
+pub fn write<const M: usize, const N: usize, W: Write>(
+    A: &Mat<M, N>,
+    mut writer: W,
+    order: Order,
+) -> Result<()> {
+    let fortran_order = if order == Order::Fortran { "True" } else { "False" };
+    let mut header = format!(
+        "{{'descr': '<f8', 'fortran_order': {fortran_order}, 'shape': ({M}, {N}), }}"
+    );
+    // Pad with spaces so the data starts on a 64-byte boundary.
+    let unpadded = MAGIC.len() + 2 + 2 + header.len() + 1;
+    header.extend(std::iter::repeat_n(' ', (64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[1, 0])?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    match order {
+        Order::Fortran => {
+            for v in A.data.iter().flatten() {
+                writer.write_all(&v.to_le_bytes())?;
+            }
+        }
+        Order::C => {
+            for i in 1..=M {
+                for j in 1..=N {
+                    writer.write_all(&A[(i, j)].to_le_bytes())?;
+                }
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[test]
+fn npy_roundtrip_test() {
+    let A = Mat::<4, 3>::rand();
+    for order in [Order::C, Order::Fortran] {
+        let mut buf = vec![];
+        write(&A, &mut buf, order).unwrap();
+        assert_eq!(buf.len(), 128 + 8 * 12);
+        let B: Mat<4, 3> = read(&buf[..]).unwrap();
+        assert_eq!(A.data, B.data);
+    }
+}
+
+/// Files as NumPy writes them, e.g. `np.save(f, np.arange(6.).reshape(2, 3))`.
+#[test]
+fn npy_numpy_test() {
+    fn npy(header: &str, data: &[R], big_endian: bool) -> Vec<u8> {
+        let mut buf = MAGIC.to_vec();
+        buf.extend([1, 0]);
+        buf.extend((header.len() as u16).to_le_bytes());
+        buf.extend(header.as_bytes());
+        for v in data {
+            buf.extend(if big_endian {
+                v.to_be_bytes()
+            } else {
+                v.to_le_bytes()
+            });
+        }
+        buf
+    }
+    let expected = Mat::from([[0., 1., 2.], [3., 4., 5.]]);
+    let c = "{'descr': '<f8', 'fortran_order': False, 'shape': (2, 3), }  \n";
+    let buf = npy(c, &[0., 1., 2., 3., 4., 5.], false);
+    assert_eq!(read::<2, 3, _>(&buf[..]).unwrap().data, expected.data);
+
+    let f = "{'descr': '>f8', 'fortran_order': True, 'shape': (2, 3), }   \n";
+    let buf = npy(f, &[0., 3., 1., 4., 2., 5.], true);
+    assert_eq!(read::<2, 3, _>(&buf[..]).unwrap().data, expected.data);
+
+    let v = "{'descr': '<f8', 'fortran_order': False, 'shape': (3,), }    \n";
+    let buf = npy(v, &[1., 2., 3.], false);
+    assert_eq!(read::<3, 1, _>(&buf[..]).unwrap().data, [[1., 2., 3.]]);
+    assert_eq!(read::<1, 3, _>(&buf[..]).unwrap().data, [[1.], [2.], [3.]]);
+    assert!(matches!(
+        read::<2, 2, _>(&buf[..]),
+        Err(Error::DimensionMismatch { expected: (2, 2), found: (3, 1) })
+    ));
+
+    let i = "{'descr': '<i8', 'fortran_order': False, 'shape': (3,), }    \n";
+    let buf = npy(i, &[1., 2., 3.], false);
+    assert!(matches!(read::<3, 1, _>(&buf[..]), Err(Error::Parse(_))));
+}
+
+#[test]
+fn npy_file_test() {
+    let path = std::env::temp_dir().join("npy_file_test.npy");
+    let A = Mat::<5, 2>::rand();
+    A.write_npy(&path, Order::C).unwrap();
+    let B = Mat::<5, 2>::read_npy(&path).unwrap();
+    assert_eq!(A.data, B.data);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,329 @@
+// NumPy .npz archives: a zip file of .npy arrays, one per name.
+// Only uncompressed archives (as written by `np.savez`) are supported.
+// https://pkware.cachefly.net/webdocs/casestudies/APPNOTE.TXT
+
+use super::npy;
+use crate::prelude::*;
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const LOCAL_HEADER: u32 = 0x04034b50;
+const CENTRAL_HEADER: u32 = 0x02014b50;
+const END_OF_CENTRAL_DIR: u32 = 0x06054b50;
+const ZIP64_EXTRA: u16 = 0x0001;
+
+/// Compression method for entries that are stored as-is.
+const STORED: u16 = 0;
+
+/// Contents of a .npz archive: .npy files, by array name.
+#[derive(Clone, Debug, Default)]
+pub struct Npz {
+    arrays: Vec<(String, Vec<u8>)>,
+}
+
+/// CRC-32 (IEEE), as used by zip.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb88320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+fn truncated() -> Error {
+    Error::Parse("truncated .npz archive".into())
+}
+
+/// `len` bytes from `start`, where both may come from a malformed file.
+fn bytes_at(buf: &[u8], start: usize, len: usize) -> Result<&[u8]> {
+    let end = start.checked_add(len).ok_or_else(truncated)?;
+    buf.get(start..end).ok_or_else(truncated)
+}
+
+/// A size or offset from the archive, as an index.
+fn index(n: u64) -> Result<usize> {
+    usize::try_from(n).map_err(|_| truncated())
+}
+
+fn u16_at(buf: &[u8], i: usize) -> Result<u16> {
+    let bytes = bytes_at(buf, i, 2)?;
+    Ok(u16::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn u32_at(buf: &[u8], i: usize) -> Result<u32> {
+    let bytes = bytes_at(buf, i, 4)?;
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn u64_at(buf: &[u8], i: usize) -> Result<u64> {
+    let bytes = bytes_at(buf, i, 8)?;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+/// Replaces 32-bit sizes and offsets that overflowed (0xffffffff) with
+/// their values from a zip64 extra field, in the order the format lists
+/// them: size, compressed size, local header offset.
+fn zip64_fields(extra: &[u8], fields: &mut [&mut u64]) -> Result<()> {
+    let mut i = 0;
+    while i + 4 <= extra.len() {
+        let (id, len) = (u16_at(extra, i)?, u16_at(extra, i + 2)? as usize);
+        if id == ZIP64_EXTRA {
+            let mut at = i + 4;
+            for field in fields.iter_mut().filter(|f| ***f == 0xffffffff) {
+                **field = u64_at(extra, at)?;
+                at += 8;
+            }
+        }
+        i += 4 + len;
+    }
+    Ok(())
+}
+
+impl Npz {
+    /// New archive with no arrays.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a .npz archive, using its central directory.
+    pub fn read<Rd: Read>(mut reader: Rd) -> Result<Self> {
+        let mut buf = vec![];
+        reader.read_to_end(&mut buf)?;
+
+        // The end of central directory record is at least 22 bytes, and
+        // is followed by a comment of at most 64KiB.
+        let eocd = (0..=buf.len().saturating_sub(22))
+            .rev()
+            .take(22 + 0xffff)
+            .find(|&i| u32_at(&buf, i).ok() == Some(END_OF_CENTRAL_DIR))
+            .ok_or_else(|| Error::Parse("not a zip archive".into()))?;
+        let count = u16_at(&buf, eocd + 10)? as usize;
+        let mut at = u32_at(&buf, eocd + 16)? as usize;
+
+        let mut arrays = vec![];
+        for _ in 0..count {
+            if u32_at(&buf, at)? != CENTRAL_HEADER {
+                return Err(Error::Parse("bad zip central directory".into()));
+            }
+            let method = u16_at(&buf, at + 10)?;
+            let mut size = u32_at(&buf, at + 20)? as u64;
+            let mut uncompressed = u32_at(&buf, at + 24)? as u64;
+            let name_len = u16_at(&buf, at + 28)? as usize;
+            let extra_len = u16_at(&buf, at + 30)? as usize;
+            let comment_len = u16_at(&buf, at + 32)? as usize;
+            let mut offset = u32_at(&buf, at + 42)? as u64;
+            let name_at = at.checked_add(46).ok_or_else(truncated)?;
+            let name = bytes_at(&buf, name_at, name_len)?;
+            let name = String::from_utf8_lossy(name).into_owned();
+            let extra = bytes_at(&buf, name_at + name_len, extra_len)?;
+            zip64_fields(
+                extra,
+                &mut [&mut uncompressed, &mut size, &mut offset],
+            )?;
+            at = name_at + name_len + extra_len + comment_len;
+
+            if method != STORED {
+                let msg = format!(
+                    "`{name}` is compressed: only archives from `np.savez` \
+                     (not `np.savez_compressed`) are supported"
+                );
+                return Err(Error::Parse(msg));
+            }
+            let local = index(offset)?;
+            if u32_at(&buf, local)? != LOCAL_HEADER {
+                return Err(Error::Parse("bad zip local header".into()));
+            }
+            // The local header was read, so local + 30 is within buf.
+            let start = local
+                + 30
+                + u16_at(&buf, local + 26)? as usize
+                + u16_at(&buf, local + 28)? as usize;
+            let data = bytes_at(&buf, start, index(size)?)?.to_vec();
+            let name = name.strip_suffix(".npy").unwrap_or(&name).to_string();
+            arrays.push((name, data));
+        }
+        Ok(Self { arrays })
+    }
+
+    /// Read a .npz archive from disk.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Serialize as an uncompressed zip archive.
+    pub fn write<W: Write>(&self, mut w: W) -> Result<()> {
+        let mut central = vec![];
+        let mut offset = 0;
+        for (name, data) in &self.arrays {
+            let name = format!("{name}.npy");
+            let crc = crc32(data);
+            // Fields shared by the local and central headers, from
+            // "version needed" through "extra field length".
+            let mut common = vec![];
+            common.extend(20u16.to_le_bytes()); // version needed: 2.0
+            common.extend(0u16.to_le_bytes()); // flags
+            common.extend(STORED.to_le_bytes());
+            common.extend(0u16.to_le_bytes()); // time: 00:00:00
+            common.extend(0x21u16.to_le_bytes()); // date: 1980-01-01
+            common.extend(crc.to_le_bytes());
+            common.extend((data.len() as u32).to_le_bytes());
+            common.extend((data.len() as u32).to_le_bytes());
+            common.extend((name.len() as u16).to_le_bytes());
+            common.extend(0u16.to_le_bytes());
+
+            w.write_all(&LOCAL_HEADER.to_le_bytes())?;
+            w.write_all(&common)?;
+            w.write_all(name.as_bytes())?;
+            w.write_all(data)?;
+
+            central.extend(CENTRAL_HEADER.to_le_bytes());
+            central.extend(20u16.to_le_bytes()); // version made by
+            central.extend(&common);
+            central.extend(0u16.to_le_bytes()); // comment length
+            central.extend(0u16.to_le_bytes()); // disk number
+            central.extend(0u16.to_le_bytes()); // internal attributes
+            central.extend(0u32.to_le_bytes()); // external attributes
+            central.extend((offset as u32).to_le_bytes());
+            central.extend(name.as_bytes());
+
+            offset += 30 + name.len() + data.len();
+        }
+        w.write_all(&central)?;
+
+        let count = (self.arrays.len() as u16).to_le_bytes();
+        w.write_all(&END_OF_CENTRAL_DIR.to_le_bytes())?;
+        w.write_all(&[0; 4])?; // disk numbers
+        w.write_all(&count)?;
+        w.write_all(&count)?;
+        w.write_all(&(central.len() as u32).to_le_bytes())?;
+        w.write_all(&(offset as u32).to_le_bytes())?;
+        w.write_all(&[0; 2])?; // comment length
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Write the archive to disk.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+
+    /// Names of the arrays, in archive order.
+    pub fn names(&self) -> Vec<&str> {
+        self.arrays.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Get the array `name`, which must be a float64 array of shape
+    /// (M, N).
+    pub fn get<const M: usize, const N: usize>(
+        &self,
+        name: &str,
+    ) -> Result<Mat<M, N>> {
+        match self.arrays.iter().find(|(n, _)| n == name) {
+            Some((_, data)) => npy::read(&data[..]),
+            None => Err(Error::MissingVariable(name.into())),
+        }
+    }
+
+    /// Set the array `name`, replacing any array of that name.
+    pub fn insert<const M: usize, const N: usize>(
+        &mut self,
+        name: &str,
+        A: &Mat<M, N>,
+    ) {
+        let mut data = vec![];
+        npy::write(A, &mut data, npy::Order::Fortran)
+            .expect("writing to a Vec cannot fail");
+        match self.arrays.iter_mut().find(|(n, _)| n == name) {
+            Some((_, d)) => *d = data,
+            None => self.arrays.push((name.into(), data)),
+        }
+    }
+}
+
+#[test]
+fn crc32_test() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xcbf43926);
+}
+
+#[test]
+fn npz_roundtrip_test() {
+    let A = Mat::<4, 3>::rand();
+    let x = Mat::<5, 1>::rand();
+    let mut f = Npz::new();
+    f.insert("A", &A);
+    f.insert("x", &x);
+    f.insert("A", &(2. * &A)); // replaces
+
+    let mut buf = vec![];
+    f.write(&mut buf).unwrap();
+    let f = Npz::read(&buf[..]).unwrap();
+    assert_eq!(f.names(), ["A", "x"]);
+    assert_eq!(f.get::<4, 3>("A").unwrap().data, (2. * A).data);
+    assert_eq!(f.get::<5, 1>("x").unwrap().data, x.data);
+    assert!(matches!(f.get::<4, 3>("B"), Err(Error::MissingVariable(_))));
+    assert!(matches!(f.get::<3, 4>("A"), Err(Error::DimensionMismatch { .. })));
+}
+
+/// A one-entry archive, laid out as `np.savez` writes it, with `size`
+/// as both sizes in the zip64 extra field.
+#[cfg(test)]
+fn zip64_archive(data: &[u8], size: u64) -> Vec<u8> {
+    let name = b"A.npy";
+
+    let mut zip64 = vec![];
+    zip64.extend(ZIP64_EXTRA.to_le_bytes());
+    zip64.extend(16u16.to_le_bytes());
+    zip64.extend(size.to_le_bytes());
+    zip64.extend(size.to_le_bytes());
+
+    let mut buf = vec![];
+    buf.extend(LOCAL_HEADER.to_le_bytes());
+    buf.extend([45, 0, 0, 0, 0, 0, 0, 0, 0x21, 0]);
+    buf.extend(crc32(data).to_le_bytes());
+    buf.extend([0xff; 8]);
+    buf.extend((name.len() as u16).to_le_bytes());
+    buf.extend((zip64.len() as u16).to_le_bytes());
+    buf.extend(name);
+    buf.extend(&zip64);
+    buf.extend(data);
+
+    let central_start = buf.len();
+    buf.extend(CENTRAL_HEADER.to_le_bytes());
+    buf.extend([45, 0, 45, 0, 0, 0, 0, 0, 0, 0, 0x21, 0]);
+    buf.extend(crc32(data).to_le_bytes());
+    buf.extend([0xff; 8]);
+    buf.extend((name.len() as u16).to_le_bytes());
+    buf.extend((zip64.len() as u16).to_le_bytes());
+    buf.extend([0; 14]); // comment length through local header offset
+    buf.extend(name);
+    buf.extend(&zip64);
+    let central_len = buf.len() - central_start;
+
+    buf.extend(END_OF_CENTRAL_DIR.to_le_bytes());
+    buf.extend([0, 0, 0, 0, 1, 0, 1, 0]);
+    buf.extend((central_len as u32).to_le_bytes());
+    buf.extend((central_start as u32).to_le_bytes());
+    buf.extend([0, 0]);
+    buf
+}
+
+/// `np.savez` forces zip64 on every entry: the local header sizes are
+/// 0xffffffff, with the real ones in a zip64 extra field.
+#[test]
+fn npz_zip64_test() {
+    let A = Mat::<2, 2>::rand();
+    let mut data = vec![];
+    npy::write(&A, &mut data, npy::Order::C).unwrap();
+    let buf = zip64_archive(&data, data.len() as u64);
+    let f = Npz::read(&buf[..]).unwrap();
+    assert_eq!(f.get::<2, 2>("A").unwrap().data, A.data);
+
+    let buf = zip64_archive(&data, u64::MAX);
+    assert!(matches!(Npz::read(&buf[..]), Err(Error::Parse(_))));
+}
@@ -0,0 +1,219 @@
+// Delimited text: CSV, TSV, and MATLAB-style whitespace-separated
+// columns (as written by `save -ascii` or `dlmwrite`).
+
+use crate::prelude::*;
+
+use std::io::{BufRead, Write};
+
+/// What separates the values on a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    /// Any run of spaces and tabs.
+    Whitespace,
+    /// A single character, e.g. ',' or '\t'.
+    Char(char),
+}
+
+/// Layout of a delimited text file, one matrix row per line.
+///
+/// Lines starting with '#' or '%' and blank lines are skipped when
+/// reading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextFormat {
+    delimiter: Delimiter,
+    skip_rows: usize,
+    precision: Option<usize>,
+}
+
+impl Default for TextFormat {
+    fn default() -> Self {
+        Self::whitespace()
+    }
+}
+
+impl TextFormat {
+    /// Comma-separated values.
+    pub fn csv() -> Self {
+        Self::whitespace().delimiter(Delimiter::Char(','))
+    }
+
+    /// Tab-separated values.
+    pub fn tsv() -> Self {
+        Self::whitespace().delimiter(Delimiter::Char('\t'))
+    }
+
+    /// Whitespace-separated values, as MATLAB reads and writes them.
+    pub fn whitespace() -> Self {
+        Self { delimiter: Delimiter::Whitespace, skip_rows: 0, precision: None }
+    }
+
+    /// Set the delimiter.
+    pub fn delimiter(mut self, delimiter: Delimiter) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Skip the first `n` lines of the file, e.g. a header row. Skipped
+    /// when reading only.
+    pub fn skip_rows(mut self, n: usize) -> Self {
+        self.skip_rows = n;
+        self
+    }
+
+    /// Write values with `digits` digits after the decimal point, in
+    /// scientific notation. By default values are written with as many
+    /// digits as it takes to read them back exactly.
+    pub fn precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+
+    fn split<'a>(&self, line: &'a str) -> Vec<&'a str> {
+        match self.delimiter {
+            Delimiter::Whitespace => line.split_whitespace().collect(),
+            Delimiter::Char(c) => line.split(c).map(str::trim).collect(),
+        }
+    }
+
+    /// Reads an M×N matrix. A vector (N = 1) can also be stored as a
+    /// single row.
+    pub fn read<const M: usize, const N: usize, B: BufRead>(
+        &self,
+        reader: B,
+    ) -> Result<Mat<M, N>> {
+        let mut rows: Vec<Vec<R>> = vec![];
+        for (n, line) in reader.lines().enumerate().skip(self.skip_rows) {
+            let line = line?;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with(['#', '%']) {
+                continue;
+            }
+            let row = self
+                .split(trimmed)
+                .into_iter()
+                .map(|v| {
+                    v.parse().map_err(|_| {
+                        Error::Parse(format!(
+                            "line {}: bad number `{v}`",
+                            n + 1
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<R>>>()?;
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(Error::Parse(format!(
+                        "line {}: expected {} values, found {}",
+                        n + 1,
+                        first.len(),
+                        row.len()
+                    )));
+                }
+            }
+            rows.push(row);
+        }
+
+        let found = (rows.len(), rows.first().map_or(0, |r| r.len()));
+        let mut A = Mat::<M, N>::zero();
+        if found == (M, N) {
+            for (i, row) in rows.iter().enumerate() {
+                for (j, v) in row.iter().enumerate() {
+                    A.data[j][i] = *v;
+                }
+            }
+        } else if N == 1 && found == (1, M) {
+            A.data[0].copy_from_slice(&rows[0]);
+        } else {
+            return Err(Error::DimensionMismatch { expected: (M, N), found });
+        }
+        Ok(A)
+    }
+
+    /// Writes an M×N matrix, one row per line.
+    pub fn write<const M: usize, const N: usize, W: Write>(
+        &self,
+        A: &Mat<M, N>,
+        mut writer: W,
+    ) -> Result<()> {
+        let delimiter = match self.delimiter {
+            Delimiter::Whitespace => " ".to_string(),
+            Delimiter::Char(c) => c.to_string(),
+        };
+        for i in 1..=M {
+            let row: Vec<String> = (1..=N)
+                .map(|j| match self.precision {
+                    Some(p) => format!("{:.p$e}", A[(i, j)]),
+                    None => format!("{:e}", A[(i, j)]),
+                })
+                .collect();
+            writeln!(writer, "{}", row.join(&delimiter))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[test]
+fn text_roundtrip_test() {
+    let A = Mat::<4, 3>::rand();
+    for format in [TextFormat::csv(), TextFormat::tsv(), TextFormat::default()]
+    {
+        let mut buf = vec![];
+        format.write(&A, &mut buf).unwrap();
+        let B: Mat<4, 3> = format.read(&buf[..]).unwrap();
+        assert_eq!(A.data, B.data);
+    }
+
+    let format = TextFormat::csv().precision(3);
+    let mut buf = vec![];
+    format.write(&Mat::from([[1., -0.0123456]]), &mut buf).unwrap();
+    assert_eq!(String::from_utf8(buf).unwrap(), "1.000e0,-1.235e-2\n");
+}
+
+#[test]
+fn text_read_test() {
+    let file = "x,y,z\n# comment\n1, 2, 3\n\n4,5,6e1\n";
+    let A: Mat<2, 3> =
+        TextFormat::csv().skip_rows(1).read(file.as_bytes()).unwrap();
+    assert_eq!(A.data, Mat::from([[1., 2., 3.], [4., 5., 60.]]).data);
+
+    let file = "% saved by MATLAB\n   1.0  2.0\t3.0\n";
+    let x: Mat<3, 1> = TextFormat::whitespace().read(file.as_bytes()).unwrap();
+    assert_eq!(x.data, [[1., 2., 3.]]);
+    let x: Mat<1, 3> = TextFormat::whitespace().read(file.as_bytes()).unwrap();
+    assert_eq!(x.data, [[1.], [2.], [3.]]);
+    let file = "1\n2\n3\n";
+    let x: Mat<3, 1> = TextFormat::whitespace().read(file.as_bytes()).unwrap();
+    assert_eq!(x.data, [[1., 2., 3.]]);
+}
+
+#[test]
+fn text_error_test() {
+    let csv = TextFormat::csv();
+    assert!(matches!(
+        csv.read::<2, 2, _>("1,2\n3,4\n5,6\n".as_bytes()),
+        Err(Error::DimensionMismatch { expected: (2, 2), found: (3, 2) })
+    ));
+    assert!(matches!(
+        csv.read::<2, 2, _>("1,2\n3\n".as_bytes()),
+        Err(Error::Parse(_))
+    ));
+    assert!(matches!(
+        csv.read::<1, 2, _>("1;2\n".as_bytes()),
+        Err(Error::Parse(_))
+    ));
+    assert!(matches!(
+        csv.read::<1, 1, _>("".as_bytes()),
+        Err(Error::DimensionMismatch { found: (0, 0), .. })
+    ));
+}
+
+#[test]
+fn text_file_test() {
+    let path = std::env::temp_dir().join("text_file_test.csv");
+    let A = Mat::<5, 2>::rand();
+    A.write_text(&path, TextFormat::csv()).unwrap();
+    let B = Mat::<5, 2>::read_text(&path, TextFormat::csv()).unwrap();
+    assert_eq!(A.data, B.data);
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,11 @@
+/// Builds a matrix from a MATLAB-style literal: commas separate the
+/// entries of a row, and semicolons separate rows.
+///
+/// `mat![1, 2; 3, 4]` is a 2×2 matrix and `mat![1; 2; 3]` a column
+/// vector. Rows of different lengths do not compile.
+#[macro_export]
+macro_rules! mat {
+    ($($($x:expr),+);+ $(;)?) => {
+        $crate::prelude::Mat::from([$([$(($x) as $crate::prelude::R),+]),+])
+    };
+}
@@ -2,10 +2,16 @@
 
 #[macro_use]
 mod assert;
+#[macro_use]
+mod macros;
+mod gallery;
 mod interpolation;
+mod io;
 mod matrix;
 mod na;
+mod parallel;
 mod prelude;
+mod random;
 
 use prelude::*;
 
@@ -14,13 +20,73 @@ fn demo() {
     let A = Mat::<5, 5>::rand();
     let b = Mat::<5, 1>::rand();
     na::cholesky(&mut Mat::<1, 1>::zero());
-    na::horners(&vec![], 1.);
-    na::backward_sub(&A, &b);
+    na::horners(&[], 1.);
+    na::lu(&mut A.clone());
+    na::backward_sub(&A.clone().into(), &b);
+    na::forward_sub(&A.clone().into(), &b);
+    A.cholesky();
+    A.lu();
+    A.lu_pivoted();
     na::power_iteration(&A);
     na::rayleigh_quotient(&b, &A);
     na::inverse_iteration(&A, 0.);
     na::rayleigh_quotient_iteration(&A);
     na::qr_decomp::gram_schmidt(&A);
+    let options = na::iterative::IterOptions::default().tol(1e-8).max_iter(10);
+    let omega = na::iterative::optimal_omega(&A);
+    if let Ok(result) = na::iterative::jacobi(&A, &b, &b, &options) {
+        println!("{} {} {:?}", result.x, result.iterations, result.residuals);
+    }
+    na::iterative::gauss_seidel(&A, &b, &b, &options);
+    na::iterative::sor(&A, &b, &b, omega, &options);
+    na::iterative::ssor(&A, &b, &b, omega, &options);
+    {
+        use na::iterative::conjugate_gradient as cg;
+        use na::preconditioner::*;
+        let S = A.t() * &A;
+        cg(&S, &b, &b, &Identity, &options);
+        cg(&S, &b, &b, &Jacobi::new(&S), &options);
+        if let Ok(ic) = IncompleteCholesky::from_mat(&S) {
+            cg(&S, &b, &b, &ic, &options);
+            ic.factor();
+        }
+        if let Ok(ilu) = IncompleteLu::from_mat(&A) {
+            na::iterative::bicgstab(&A, &b, &b, &ilu, &options);
+            ilu.factors();
+        }
+        IncompleteLu::threshold(&Sparse::from_mat(&A), 1e-3, 2);
+        for side in [na::iterative::Side::Left, na::iterative::Side::Right] {
+            na::iterative::gmres(&A, &b, &b, 5, &Identity, side, &options);
+        }
+        na::iterative::bicgstab(&A, &b, &b, &Identity, &options);
+        na::iterative::minres(&S, &b, &b, &Identity, &options);
+    }
+    let refined = na::refinement::solve_mixed(&A, &b, 5);
+    for step in &refined.steps {
+        println!("{} {}", step.backward_error, step.forward_error);
+    }
+    if let Ok(Solution { x, method, condition }) = A.solve(&b) {
+        println!("{x} by {method:?}, κ ≈ {condition}");
+    }
+    let lu = Lu::new(&A);
+    lu.factors();
+    println!("{} {}", lu.det(), lu.condition());
+    let refined = na::refinement::refine(&A, &b, |r| lu.solve(r), 5);
+    println!("{} {}", refined.x, refined.converged);
+    lu.solve_transpose(&lu.solve_many(&b));
+    if let Ok(cholesky) = Cholesky::new(&A) {
+        cholesky.solve_transpose(&cholesky.solve_many(&b));
+        cholesky.factor();
+        println!("{} {}", cholesky.det(), cholesky.condition());
+    }
+    let qr = Qr::new(&A);
+    qr.factors();
+    qr.det();
+    qr.solve_transpose(&qr.solve_many(&b));
+    parallel::set_num_threads(1);
+    parallel::with_num_threads(2, || A.qr_householder());
+    random::set_seed(random::seed());
+    random::random::<R>();
 }
 
 fn main() -> Result<()> {
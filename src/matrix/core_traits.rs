@@ -1,4 +1,4 @@
-use super::{Mat, R};
+use super::{Mat, MatFormat, R};
 
 use std::fmt;
 use std::ops::{Index, IndexMut};
@@ -6,31 +6,13 @@ use std::ops::{Index, IndexMut};
 impl<const M: usize, const N: usize> fmt::Display for Mat<M, N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Matrix")?;
-        for i in 1..=M {
-            write!(f, "  ")?;
-            for j in 1..=N {
-                write!(f, "{:>8.4}", self[(i, j)])?;
-            }
-            if i < M {
-                writeln!(f)?;
-            }
-        }
-        Ok(())
+        MatFormat::new().write(self, f)
     }
 }
 impl<const M: usize, const N: usize> fmt::Debug for Mat<M, N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Matrix")?;
-        for i in 1..=M {
-            write!(f, "  ")?;
-            for j in 1..=N {
-                write!(f, "{:>8.10}", self[(i, j)])?;
-            }
-            if i < M {
-                writeln!(f)?;
-            }
-        }
-        Ok(())
+        MatFormat::new().precision(10).write(self, f)
     }
 }
 
@@ -66,6 +48,6 @@ impl<const N: usize> IndexMut<usize> for Mat<N, 1> {
 
 impl<const M: usize, const N: usize> AsRef<Mat<M, N>> for Mat<M, N> {
     fn as_ref(&self) -> &Mat<M, N> {
-        &self
+        self
     }
 }
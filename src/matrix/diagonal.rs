@@ -0,0 +1,128 @@
+use super::*;
+
+use std::fmt;
+use std::ops::{Index, IndexMut, Mul};
+
+/// Diagonal N×N matrix. Only the diagonal is stored.
+#[derive(Clone)]
+pub struct Diagonal<const N: usize> {
+    diag: Mat<N, 1>,
+}
+
+/// Takes the vector as the diagonal.
+impl<const N: usize> From<Mat<N, 1>> for Diagonal<N> {
+    fn from(diag: Mat<N, 1>) -> Self {
+        Self { diag }
+    }
+}
+
+impl<const N: usize> Diagonal<N> {
+    /// Create a new identity matrix.
+    pub fn eye() -> Self {
+        Self { diag: Mat::from_fn(|_, _| 1.) }
+    }
+
+    /// Extracts the diagonal of a dense matrix.
+    pub fn from_mat(A: &Mat<N, N>) -> Self {
+        Self { diag: Mat::from_fn(|i, _| A[(i, i)]) }
+    }
+
+    /// The diagonal, as a column vector.
+    pub fn diag(&self) -> &Mat<N, 1> {
+        &self.diag
+    }
+
+    /// Expand into a dense matrix.
+    pub fn to_mat(&self) -> Mat<N, N> {
+        Mat::from_fn(|i, j| if i == j { self[i] } else { 0. })
+    }
+
+    /// Determinant: product of elements on the diagonal.
+    pub fn det(&self) -> R {
+        self.diag.data[0].iter().product()
+    }
+
+    /// Inverse: reciprocal of elements on the diagonal.
+    pub fn inverse(&self) -> Self {
+        Self { diag: Mat::from_fn(|i, _| self[i].recip()) }
+    }
+
+    /// Solve Dx = b, with D := self.
+    pub fn solve(&self, b: &Mat<N, 1>) -> Mat<N, 1> {
+        Mat::from_fn(|i, _| b[i] / self[i])
+    }
+}
+
+/// Indexes into the diagonal.
+impl<const N: usize> Index<usize> for Diagonal<N> {
+    type Output = R;
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.diag[index]
+    }
+}
+
+/// Indexes into the diagonal.
+impl<const N: usize> IndexMut<usize> for Diagonal<N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.diag[index]
+    }
+}
+
+/// Scales the rows of `rhs`.
+impl<const N: usize, const K: usize> Mul<&Mat<N, K>> for &Diagonal<N> {
+    type Output = Mat<N, K>;
+    fn mul(self, rhs: &Mat<N, K>) -> Self::Output {
+        Mat::from_fn(|i, j| self[i] * rhs[(i, j)])
+    }
+}
+
+/// Scales the columns of `self`.
+impl<const M: usize, const N: usize> Mul<&Diagonal<N>> for &Mat<M, N> {
+    type Output = Mat<M, N>;
+    fn mul(self, rhs: &Diagonal<N>) -> Self::Output {
+        Mat::from_fn(|i, j| self[(i, j)] * rhs[j])
+    }
+}
+
+impl<const N: usize> Mul<&Diagonal<N>> for &Diagonal<N> {
+    type Output = Diagonal<N>;
+    fn mul(self, rhs: &Diagonal<N>) -> Self::Output {
+        Diagonal { diag: Mat::from_fn(|i, _| self[i] * rhs[i]) }
+    }
+}
+
+impl<const N: usize> fmt::Display for Diagonal<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_mat())
+    }
+}
+
+impl<const N: usize> fmt::Debug for Diagonal<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.to_mat())
+    }
+}
+
+#[test]
+fn diagonal_product_test() {
+    const N: usize = 6;
+    for _ in 0..REPS {
+        let (D1, D2) = (Diagonal::<N>::from_mat(&Mat::rand()), Diagonal::eye());
+        let A = Mat::<N, N>::rand();
+        assert_eq_mat!(&D1 * &A, D1.to_mat() * &A);
+        assert_eq_mat!(&A * &D1, &A * D1.to_mat());
+        assert_eq_mat!((&D1 * &D2).to_mat(), D1.to_mat() * D2.to_mat());
+    }
+}
+
+#[test]
+fn diagonal_inverse_test() {
+    const N: usize = 6;
+    for _ in 0..REPS {
+        let D = Diagonal::<N>::from(Mat::rand());
+        let b = Mat::<N, 1>::rand();
+        assert_eq_mat!(&D * &D.solve(&b), b);
+        assert_eq_mat!(&D.inverse() * &b, D.solve(&b));
+        assert_eq_tol!(D.det(), D.to_mat().lu().1.det(), 1e-9);
+    }
+}
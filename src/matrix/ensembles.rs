@@ -0,0 +1,244 @@
+// Random matrices with controlled structure, spectra and conditioning,
+// for stability experiments. Extends matlab/+random.
+//
+// Each generator draws from the crate-wide generator (see
+// `random::set_seed`), and has a `_with` variant taking a generator.
+
+use super::*;
+
+use std::f64::consts::PI;
+
+/// A standard normal sample, by the Box-Muller transform.
+fn gaussian<G: Rng + ?Sized>(rng: &mut G) -> R {
+    // 1 - u is in (0, 1], so its log is finite.
+    let u: R = 1. - rng.gen::<R>();
+    let v: R = rng.gen();
+    (-2. * u.ln()).sqrt() * (2. * PI * v).cos()
+}
+
+impl<const M: usize, const N: usize> Mat<M, N> {
+    /// Matrix of independent standard normal entries.
+    pub fn randn() -> Self {
+        random::with_rng(Self::randn_with)
+    }
+
+    pub fn randn_with<G: Rng + ?Sized>(rng: &mut G) -> Self {
+        let mut A = Self::zero();
+        A.data.iter_mut().flatten().for_each(|v| *v = gaussian(rng));
+        A
+    }
+
+    /// Random matrix with the given singular values, in any order:
+    /// A = UΣVᵀ with U and V Haar-random orthogonal matrices.
+    ///
+    /// `sigma` MUST have min(M, N) entries.
+    pub fn with_singular_values(sigma: &[R]) -> Self {
+        random::with_rng(|rng| Self::with_singular_values_with(rng, sigma))
+    }
+
+    pub fn with_singular_values_with<G: Rng + ?Sized>(
+        rng: &mut G,
+        sigma: &[R],
+    ) -> Self {
+        assert_eq!(sigma.len(), M.min(N), "need min(M, N) singular values");
+        let U = Mat::<M, M>::orthogonal_with(rng);
+        let V = Mat::<N, N>::orthogonal_with(rng);
+        // UΣ scales the first min(M, N) columns of U.
+        let US = Mat::<M, N>::from_fn(|i, j| match sigma.get(j - 1) {
+            Some(s) => U[(i, j)] * s,
+            None => 0.,
+        });
+        US * V.t()
+    }
+
+    /// Random matrix with 2-norm condition number `kappa`, with singular
+    /// values spaced geometrically from 1 down to 1/`kappa` (the "randsvd"
+    /// matrices of Higham's test matrix gallery).
+    pub fn with_condition_number(kappa: R) -> Self {
+        random::with_rng(|rng| Self::with_condition_number_with(rng, kappa))
+    }
+
+    pub fn with_condition_number_with<G: Rng + ?Sized>(
+        rng: &mut G,
+        kappa: R,
+    ) -> Self {
+        assert!(kappa >= 1., "condition numbers are at least 1");
+        let k = M.min(N);
+        let sigma: Vec<R> = (0..k)
+            .map(|i| match k {
+                1 => 1.,
+                _ => kappa.powf(-(i as R) / (k - 1) as R),
+            })
+            .collect();
+        Self::with_singular_values_with(rng, &sigma)
+    }
+
+    /// Random matrix with entries in [0, 1) within `lower` diagonals
+    /// below the main diagonal and `upper` diagonals above it, and zeros
+    /// elsewhere.
+    pub fn banded(lower: usize, upper: usize) -> Self {
+        random::with_rng(|rng| Self::banded_with(rng, lower, upper))
+    }
+
+    pub fn banded_with<G: Rng + ?Sized>(
+        rng: &mut G,
+        lower: usize,
+        upper: usize,
+    ) -> Self {
+        Self::from_fn(|i, j| {
+            if i <= j + lower && j <= i + upper {
+                rng.gen()
+            } else {
+                0.
+            }
+        })
+    }
+}
+
+impl<const N: usize> Mat<N, N> {
+    /// Haar-random (uniformly distributed) orthogonal matrix: the Q of
+    /// a Gaussian matrix, with signs fixed so that R has a positive
+    /// diagonal (Mezzadri, 2007).
+    pub fn orthogonal() -> Self {
+        random::with_rng(Self::orthogonal_with)
+    }
+
+    pub fn orthogonal_with<G: Rng + ?Sized>(rng: &mut G) -> Self {
+        let (mut Q, R) = Self::randn_with(rng).qr_householder();
+        for j in 1..=N {
+            if R[(j, j)] < 0. {
+                Q.col_mut(j).data[0].iter_mut().for_each(|v| *v = -*v);
+            }
+        }
+        Q
+    }
+
+    /// Random symmetric positive definite matrix with the given
+    /// eigenvalues: QΛQᵀ with Q Haar-random orthogonal.
+    ///
+    /// The eigenvalues MUST be positive.
+    pub fn spd_with_eigenvalues(lambda: &Mat<N, 1>) -> Self {
+        random::with_rng(|rng| Self::spd_with_eigenvalues_with(rng, lambda))
+    }
+
+    pub fn spd_with_eigenvalues_with<G: Rng + ?Sized>(
+        rng: &mut G,
+        lambda: &Mat<N, 1>,
+    ) -> Self {
+        assert!(
+            lambda.data[0].iter().all(|&l| l > 0.),
+            "eigenvalues of an SPD matrix are positive"
+        );
+        let Q = Self::orthogonal_with(rng);
+        let A = &Q * &Diagonal::from(lambda.clone()) * Q.t();
+        // Symmetric to the last bit.
+        Mat::from_fn(|i, j| (A[(i, j)] + A[(j, i)]) / 2.)
+    }
+
+    /// Random tridiagonal matrix with entries in [0, 1).
+    pub fn tridiagonal() -> Self {
+        Self::banded(1, 1)
+    }
+
+    pub fn tridiagonal_with<G: Rng + ?Sized>(rng: &mut G) -> Self {
+        Self::banded_with(rng, 1, 1)
+    }
+}
+
+/// Singular values, in ascending order, from the eigenvalues of AᵀA.
+#[cfg(test)]
+fn singular_values<const M: usize, const N: usize>(A: &Mat<M, N>) -> Vec<R> {
+    let AtA = Symmetric::from_mat(&(A.t() * A), 1e-9).unwrap();
+    let (lambda, _) = AtA.eigen().unwrap();
+    lambda.data[0].iter().map(|l| l.max(0.).sqrt()).collect()
+}
+
+#[test]
+fn randn_test() {
+    let A = Mat::<100, 100>::randn();
+    let n = (100 * 100) as R;
+    let mean = A.data.iter().flatten().sum::<R>() / n;
+    let var = A.data.iter().flatten().map(|v| (v - mean).powi(2)).sum::<R>()
+        / (n - 1.);
+    assert!(mean.abs() < 0.05, "mean {mean}");
+    assert!((var - 1.).abs() < 0.07, "variance {var}");
+}
+
+#[test]
+fn orthogonal_test() {
+    for _ in 0..SMALL_REPS {
+        let Q = Mat::<6, 6>::orthogonal();
+        assert!(max_abs_diff(&(Q.t() * &Q), &Mat::eye()) < 1e-12);
+    }
+    // Haar: the entries all have mean 0, unlike a plain QR, whose first
+    // column lies mostly in one orthant.
+    let mut first_col = 0.;
+    for _ in 0..SMALL_REPS {
+        first_col += Mat::<4, 4>::orthogonal()[(1, 1)];
+    }
+    assert!((first_col / SMALL_REPS as R).abs() < 0.05);
+}
+
+#[test]
+fn spd_with_eigenvalues_test() {
+    let lambda = mat![1e-3; 0.5; 2; 7; 100];
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<5, 5>::spd_with_eigenvalues(&lambda);
+        let S = Symmetric::from_mat(&A, 0.).unwrap();
+        let (found, _) = S.eigen().unwrap();
+        assert_eq_mat!(found, lambda, 1e-9);
+        S.cholesky();
+    }
+}
+
+#[test]
+fn symmetric_positive_definite_test() {
+    // Shifting by I alone leaves negative eigenvalues at this size.
+    const N: usize = 16;
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::symmetric_positive_definite();
+        let (lambda, _) = Symmetric::from_mat(&A, 0.).unwrap().eigen().unwrap();
+        assert!(lambda[1] > 0., "{lambda:?}");
+    }
+}
+
+#[test]
+fn singular_values_test() {
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<6, 4>::with_singular_values(&[3., 1., 4., 0.5]);
+        let sigma = singular_values(&A);
+        let expected: [R; 4] = [0.5, 1., 3., 4.];
+        for (found, expected) in sigma.iter().zip(expected) {
+            assert_eq_tol!(*found, expected, 1e-9);
+        }
+        let B = Mat::<3, 5>::with_singular_values(&[2., 2., 2.]);
+        assert!(
+            max_abs_diff(&(&B * B.t()), &(4. * Mat::<3, 3>::eye())) < 1e-12
+        );
+    }
+}
+
+#[test]
+fn condition_number_test() {
+    // Through AᵀA, κ² must stay well below 1/ε.
+    let kappas: [R; 3] = [1., 10., 1e4];
+    for kappa in kappas {
+        let A = Mat::<5, 5>::with_condition_number(kappa);
+        let sigma = singular_values(&A);
+        assert!((sigma[4] - 1.).abs() < 1e-9);
+        assert_eq_tol!(sigma[4] / sigma[0], kappa, 1e-6);
+    }
+}
+
+#[test]
+fn banded_test() {
+    let A = Mat::<6, 5>::banded(2, 1);
+    for i in 1..=6 {
+        for j in 1..=5 {
+            let in_band = j + 2 >= i && j <= i + 1;
+            assert_eq!(A[(i, j)] != 0., in_band, "({i}, {j})");
+        }
+    }
+    let T = Mat::<5, 5>::tridiagonal();
+    assert!(T[(3, 1)] == 0. && T[(1, 3)] == 0. && T[(2, 1)] != 0.);
+}
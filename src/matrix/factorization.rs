@@ -0,0 +1,307 @@
+// Factorizations as values: factor a matrix once, then solve with it as
+// many times as needed, for the cost of substitutions alone.
+
+use super::*;
+use crate::na::{Diag, Transpose, Triangle};
+
+/// LU factorization with partial pivoting, PA = LU, where L has a unit
+/// diagonal.
+#[derive(Debug, Clone)]
+pub struct Lu<const N: usize> {
+    P: Permutation<N>,
+    L: LowerTriangular<N>,
+    U: UpperTriangular<N>,
+    /// ‖A‖₁, for the condition estimate.
+    norm: R,
+}
+
+impl<const N: usize> Lu<N> {
+    /// Factor A.
+    pub fn new(A: &Mat<N, N>) -> Self {
+        let mut LU = A.clone();
+        let P = na::lu_partial_pivot(&mut LU);
+        Self::from_packed(LU, P, A.l1_norm())
+    }
+
+    /// Factor a banded A, with `lower` nonzero diagonals below the main
+    /// diagonal and `upper` above it, by `na::lu_banded`.
+    pub fn banded(A: &Mat<N, N>, lower: usize, upper: usize) -> Self {
+        let mut LU = A.clone();
+        let P = na::lu_banded(&mut LU, lower, upper);
+        Self::from_packed(LU, P, A.l1_norm())
+    }
+
+    /// From L and U packed in one matrix, as `na::lu` writes them.
+    fn from_packed(LU: Mat<N, N>, P: Permutation<N>, norm: R) -> Self {
+        let L = Mat::from_fn(|i, j| if i == j { 1. } else { LU[(i, j)] });
+        Self { P, L: L.into(), U: LU.into(), norm }
+    }
+
+    /// (P, L, U).
+    pub fn factors(
+        &self,
+    ) -> (&Permutation<N>, &LowerTriangular<N>, &UpperTriangular<N>) {
+        (&self.P, &self.L, &self.U)
+    }
+
+    /// Whether U has a zero pivot, so that A is singular.
+    pub fn is_singular(&self) -> bool {
+        (1..=N).any(|i| self.U[(i, i)] == 0.)
+    }
+
+    /// Solve Ax = b.
+    pub fn solve(&self, b: &Mat<N, 1>) -> Mat<N, 1> {
+        self.solve_many(b)
+    }
+
+    /// Solve Aᵀx = b.
+    pub fn solve_transpose(&self, b: &Mat<N, 1>) -> Mat<N, 1> {
+        let y = self.U.solve_transpose(b);
+        let (lower, unit) = (Triangle::Lower, Diag::Unit);
+        &self.P.t()
+            * &na::trsm(self.L.as_mat(), &y, lower, Transpose::Yes, unit)
+    }
+
+    /// Solve AX = B, for all columns of B at once.
+    pub fn solve_many<const K: usize>(&self, B: &Mat<N, K>) -> Mat<N, K> {
+        let PB = &self.P * B;
+        let (lower, unit) = (Triangle::Lower, Diag::Unit);
+        let Y = na::trsm(self.L.as_mat(), &PB, lower, Transpose::No, unit);
+        self.U.solve(&Y)
+    }
+
+    /// det(A) = sign(P)·det(U).
+    pub fn det(&self) -> R {
+        self.P.sign() * self.U.det()
+    }
+
+    /// Estimate of κ₁(A) = ‖A‖₁‖A⁻¹‖₁; infinite if A is singular.
+    pub fn condition(&self) -> R {
+        if self.is_singular() {
+            return R::INFINITY;
+        }
+        let inverse = na::inverse_l1_norm_estimate(
+            |b| self.solve(b),
+            |b| self.solve_transpose(b),
+        );
+        self.norm * inverse
+    }
+}
+
+/// Cholesky factorization A = LLᵀ of a symmetric positive definite A.
+#[derive(Debug, Clone)]
+pub struct Cholesky<const N: usize> {
+    L: LowerTriangular<N>,
+    /// ‖A‖₁, for the condition estimate.
+    norm: R,
+}
+
+impl<const N: usize> Cholesky<N> {
+    /// Factor A, reading only its lower half. Fails with
+    /// `Error::NotPositiveDefinite` if a pivot is not positive.
+    pub fn new(A: &Mat<N, N>) -> Result<Self> {
+        let mut L = A.clone();
+        na::cholesky(&mut L);
+        if !(1..=N).all(|i| L[(i, i)] > 0.) {
+            return Err(Error::NotPositiveDefinite);
+        }
+        Ok(Self { L: L.into(), norm: A.l1_norm() })
+    }
+
+    /// L.
+    pub fn factor(&self) -> &LowerTriangular<N> {
+        &self.L
+    }
+
+    /// Solve Ax = b.
+    pub fn solve(&self, b: &Mat<N, 1>) -> Mat<N, 1> {
+        self.solve_many(b)
+    }
+
+    /// Solve Aᵀx = b, which is Ax = b, as A is symmetric.
+    pub fn solve_transpose(&self, b: &Mat<N, 1>) -> Mat<N, 1> {
+        self.solve(b)
+    }
+
+    /// Solve AX = B, for all columns of B at once.
+    pub fn solve_many<const K: usize>(&self, B: &Mat<N, K>) -> Mat<N, K> {
+        self.L.solve_transpose(&self.L.solve(B))
+    }
+
+    /// det(A) = det(L)².
+    pub fn det(&self) -> R {
+        self.L.det().powi(2)
+    }
+
+    /// Estimate of κ₁(A) = ‖A‖₁‖A⁻¹‖₁.
+    pub fn condition(&self) -> R {
+        let solve = |b: &Mat<N, 1>| self.solve(b);
+        self.norm * na::inverse_l1_norm_estimate(solve, solve)
+    }
+}
+
+/// Reduced Householder QR factorization A = QR of an M×N matrix, with
+/// M ≥ N.
+#[derive(Debug, Clone)]
+pub struct Qr<const M: usize, const N: usize> {
+    Q: Mat<M, N>,
+    R: UpperTriangular<N>,
+}
+
+impl<const M: usize, const N: usize> Qr<M, N> {
+    /// Factor A. This requires M ≥ N.
+    pub fn new(A: &Mat<M, N>) -> Self {
+        let (Q, R) = A.qr_householder_reduced();
+        Self { Q, R }
+    }
+
+    /// (Q, R).
+    pub fn factors(&self) -> (&Mat<M, N>, &UpperTriangular<N>) {
+        (&self.Q, &self.R)
+    }
+
+    /// Whether R has a zero on its diagonal, so that A is rank
+    /// deficient.
+    pub fn is_singular(&self) -> bool {
+        (1..=N).any(|i| self.R[(i, i)] == 0.)
+    }
+
+    /// The least squares solution of Ax = b (the solution, if A is
+    /// square).
+    pub fn solve(&self, b: &Mat<M, 1>) -> Mat<N, 1> {
+        self.solve_many(b)
+    }
+
+    /// The minimum norm solution of Aᵀx = b: as Aᵀ = RᵀQᵀ, x = QR⁻ᵀb is
+    /// in the column space of A.
+    pub fn solve_transpose(&self, b: &Mat<N, 1>) -> Mat<M, 1> {
+        &self.Q * self.R.solve_transpose(b)
+    }
+
+    /// Solve AX = B in the least squares sense, for all columns of B
+    /// at once.
+    pub fn solve_many<const K: usize>(&self, B: &Mat<M, K>) -> Mat<N, K> {
+        self.R.solve(&(self.Q.t() * B))
+    }
+
+    /// Estimate of κ₁(R), which is κ₂(A) = κ₂(R) up to a factor of N;
+    /// infinite if A is rank deficient.
+    pub fn condition(&self) -> R {
+        let T = &self.R;
+        triangular_condition(
+            T.as_mat(),
+            |b| T.solve(b),
+            |b| T.solve_transpose(b),
+        )
+        .unwrap_or(R::INFINITY)
+    }
+}
+
+impl<const N: usize> Qr<N, N> {
+    /// det(A) = det(Q)·det(R), where Q is a product of N reflections.
+    pub fn det(&self) -> R {
+        let sign = if N.is_multiple_of(2) { 1. } else { -1. };
+        sign * self.R.det()
+    }
+}
+
+/// κ₁ of a triangular matrix T, given solves with T and Tᵀ, or
+/// `Error::Breakdown` if it's singular.
+pub(super) fn triangular_condition<const N: usize>(
+    T: &Mat<N, N>,
+    solve: impl Fn(&Mat<N, 1>) -> Mat<N, 1>,
+    solve_transpose: impl Fn(&Mat<N, 1>) -> Mat<N, 1>,
+) -> Result<R> {
+    if (1..=N).any(|i| T[(i, i)] == 0.) {
+        return Err(Error::Breakdown);
+    }
+    let inverse = na::inverse_l1_norm_estimate(solve, solve_transpose);
+    Ok(T.l1_norm() * inverse)
+}
+
+#[test]
+fn lu_test() {
+    const N: usize = 6;
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::with_condition_number(1e3);
+        let b = Mat::<N, 1>::rand();
+        let lu = Lu::new(&A);
+        let (P, L, U) = lu.factors();
+        assert!((L * U.as_mat() - P * &A).l1_norm() < 1e-12);
+        assert!((&b - &A * lu.solve(&b)).l2_norm() < 1e-9);
+        assert!((&b - A.t() * lu.solve_transpose(&b)).l2_norm() < 1e-9);
+        let X = lu.solve_many(&Mat::<N, N>::eye());
+        assert!((&A * &X - Mat::eye()).l1_norm() < 1e-9);
+        assert_eq_tol!(lu.det() * Lu::new(&X).det(), 1. as R, 1e-9);
+        let exact = A.l1_norm() * X.l1_norm();
+        assert!(lu.condition() <= exact * (1. + 1e-6));
+        assert!(lu.condition() >= exact / 10.);
+    }
+    // Nearly singular, where the estimate matters most. κ₁ is within a
+    // factor of N of κ₂.
+    let A = Mat::<N, N>::with_condition_number(1e12);
+    let lu = Lu::new(&A);
+    let exact = A.l1_norm() * lu.solve_many(&Mat::<N, N>::eye()).l1_norm();
+    assert!(lu.condition() <= exact * (1. + 1e-3));
+    assert!(lu.condition() >= exact / 10.);
+    assert!(lu.condition() > 1e12 / N as R);
+    assert!(Lu::new(&mat![1, 2; 2, 4]).is_singular());
+    assert_eq!(Lu::new(&mat![1, 2; 2, 4]).condition(), R::INFINITY);
+    assert_eq_tol!(Lu::new(&mat![0, 1; 1, 0]).det(), -1. as R, 1e-12);
+}
+
+#[test]
+fn lu_banded_factorization_test() {
+    const N: usize = 10;
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::banded(2, 1);
+        let b = Mat::<N, 1>::rand();
+        let (banded, dense) = (Lu::banded(&A, 2, 1), Lu::new(&A));
+        assert_eq_mat!(banded.solve(&b), dense.solve(&b), 1e-6);
+        assert_eq_tol!(banded.det(), dense.det(), 1e-9);
+    }
+}
+
+#[test]
+fn cholesky_factorization_test() {
+    const N: usize = 6;
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::symmetric_positive_definite();
+        let B = Mat::<N, 3>::rand();
+        let cholesky = Cholesky::new(&A).unwrap();
+        let L = cholesky.factor();
+        assert!((L * L.t().as_mat() - &A).l1_norm() < 1e-12);
+        let X = cholesky.solve_many(&B);
+        assert!((&A * &X - &B).l1_norm() < 1e-9);
+        assert_eq_tol!(cholesky.det(), Lu::new(&A).det(), 1e-9);
+        assert_eq_tol!(cholesky.condition(), Lu::new(&A).condition(), 1e-6);
+    }
+    let indefinite = mat![1, 2; 2, 1];
+    assert!(matches!(
+        Cholesky::new(&indefinite),
+        Err(Error::NotPositiveDefinite)
+    ));
+}
+
+#[test]
+fn qr_factorization_test() {
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<8, 3>::rand();
+        let qr = Qr::new(&A);
+        let b = Mat::<8, 1>::rand();
+        assert_eq_mat!(qr.solve(&b), A.solve_lls(&b), 1e-6);
+        // Minimum norm: Aᵀx = c, with x in the column space of A.
+        let c = Mat::<3, 1>::rand();
+        let x = qr.solve_transpose(&c);
+        assert!((A.t() * &x - &c).l2_norm() < 1e-9);
+        assert!((&x - &A * A.solve_lls(&x)).l2_norm() < 1e-9);
+        let B = Mat::<8, 2>::rand();
+        let X = qr.solve_many(&B);
+        assert!((A.t() * (&B - &A * &X)).l1_norm() < 1e-9);
+        assert!(qr.condition() >= 1.);
+
+        const N: usize = 5;
+        let A = Mat::<N, N>::rand();
+        assert_eq_tol!(Qr::new(&A).det(), Lu::new(&A).det(), 1e-9);
+    }
+}
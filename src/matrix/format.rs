@@ -0,0 +1,315 @@
+// Configurable text output of matrices, for reports and other tools.
+
+use super::{Mat, R};
+
+use std::fmt;
+
+/// How numbers are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notation {
+    /// Always `123.4560`.
+    Fixed,
+    /// Always `1.2346e2`.
+    Scientific,
+    /// Fixed for numbers of moderate size, scientific for very large or
+    /// very small ones.
+    Auto,
+}
+
+/// Which side of the column numbers are aligned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+/// What the output looks like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Rows of numbers, one per line.
+    Plain,
+    /// A LaTeX `bmatrix` environment.
+    Latex,
+    /// A MATLAB matrix literal, `[1 2; 3 4]`.
+    Matlab,
+    /// A NumPy array literal, `np.array([[1, 2], [3, 4]])`.
+    Numpy,
+}
+
+/// Builder for the text output of a matrix.
+///
+/// ```ignore
+/// let latex = A.display(MatFormat::new().style(Style::Latex).precision(2));
+/// println!("{latex}");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatFormat {
+    precision: usize,
+    notation: Notation,
+    align: Align,
+    style: Style,
+    max_rows: Option<usize>,
+    max_cols: Option<usize>,
+}
+
+impl Default for MatFormat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MatFormat {
+    /// 4 digits after the decimal point, auto notation, right-aligned
+    /// plain text, with no truncation.
+    pub fn new() -> Self {
+        Self {
+            precision: 4,
+            notation: Notation::Auto,
+            align: Align::Right,
+            style: Style::Plain,
+            max_rows: None,
+            max_cols: None,
+        }
+    }
+
+    /// Number of digits after the decimal point.
+    pub fn precision(mut self, digits: usize) -> Self {
+        self.precision = digits;
+        self
+    }
+
+    pub fn notation(mut self, notation: Notation) -> Self {
+        self.notation = notation;
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Show at most `rows` rows and `cols` columns (at least 2 each): the
+    /// first and last few, with an ellipsis in between.
+    pub fn truncate(mut self, rows: usize, cols: usize) -> Self {
+        self.max_rows = Some(rows.max(2));
+        self.max_cols = Some(cols.max(2));
+        self
+    }
+
+    fn number(&self, x: R) -> String {
+        if !x.is_finite() {
+            let inf = if x < 0. { "-" } else { "" };
+            return match (self.style, x.is_nan()) {
+                (Style::Plain, _) => format!("{x}"),
+                (Style::Latex, true) => r"\mathrm{NaN}".into(),
+                (Style::Latex, false) => format!(r"{inf}\infty"),
+                (Style::Matlab, true) => "NaN".into(),
+                (Style::Matlab, false) => format!("{inf}Inf"),
+                (Style::Numpy, true) => "np.nan".into(),
+                (Style::Numpy, false) => format!("{inf}np.inf"),
+            };
+        }
+        let p = self.precision;
+        let scientific = match self.notation {
+            Notation::Fixed => false,
+            Notation::Scientific => true,
+            Notation::Auto => x != 0. && !(1e-3..1e5).contains(&x.abs()),
+        };
+        if !scientific {
+            return format!("{x:.p$}");
+        }
+        let s = format!("{x:.p$e}");
+        match (self.style, s.split_once('e')) {
+            (Style::Latex, Some((mantissa, exp))) => {
+                format!(r"{mantissa} \times 10^{{{exp}}}")
+            }
+            _ => s,
+        }
+    }
+
+    /// Ellipses for hidden rows, hidden columns, and both.
+    fn ellipses(&self) -> [&'static str; 3] {
+        match self.style {
+            Style::Latex => [r"\vdots", r"\cdots", r"\ddots"],
+            _ => ["...", "...", "..."],
+        }
+    }
+
+    /// Writes `A` in this format.
+    pub fn write<const M: usize, const N: usize>(
+        &self,
+        A: &Mat<M, N>,
+        f: &mut impl fmt::Write,
+    ) -> fmt::Result {
+        // Indices of the rows (or columns) to show; `None` for the gap.
+        fn shown(n: usize, max: Option<usize>) -> Vec<Option<usize>> {
+            match max {
+                Some(max) if n > max => {
+                    let (head, tail) = (max.div_ceil(2), max / 2);
+                    let head = (1..=head).map(Some);
+                    let tail = (n - tail + 1..=n).map(Some);
+                    head.chain([None]).chain(tail).collect()
+                }
+                _ => (1..=n).map(Some).collect(),
+            }
+        }
+        let (rows, cols) = (shown(M, self.max_rows), shown(N, self.max_cols));
+        let [vdots, cdots, ddots] = self.ellipses();
+        let cells: Vec<Vec<String>> = rows
+            .iter()
+            .map(|i| {
+                cols.iter()
+                    .map(|j| match (i, j) {
+                        (Some(i), Some(j)) => self.number(A[(*i, *j)]),
+                        (None, Some(_)) => vdots.into(),
+                        (Some(_), None) => cdots.into(),
+                        (None, None) => ddots.into(),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // LaTeX lines up the columns itself.
+        let widths: Vec<usize> = (0..cols.len())
+            .map(|j| match self.style {
+                Style::Latex => 0,
+                _ => cells
+                    .iter()
+                    .map(|r| r[j].chars().count())
+                    .max()
+                    .unwrap_or(0),
+            })
+            .collect();
+        let lines: Vec<Vec<String>> = cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&widths)
+                    .map(|(s, &w)| match self.align {
+                        Align::Left => format!("{s:<w$}"),
+                        Align::Right => format!("{s:>w$}"),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        match self.style {
+            Style::Plain => {
+                let lines: Vec<_> = lines
+                    .iter()
+                    .map(|r| format!("  {}", r.join("  ")))
+                    .collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+            Style::Latex => {
+                let lines: Vec<_> = lines
+                    .iter()
+                    .map(|r| format!("  {}", r.join(" & ")))
+                    .collect();
+                writeln!(f, r"\begin{{bmatrix}}")?;
+                writeln!(f, "{}", lines.join(" \\\\\n"))?;
+                write!(f, r"\end{{bmatrix}}")
+            }
+            Style::Matlab => {
+                let lines: Vec<_> =
+                    lines.iter().map(|r| r.join("  ")).collect();
+                write!(f, "[{}]", lines.join(";\n "))
+            }
+            Style::Numpy => {
+                let lines: Vec<_> = lines
+                    .iter()
+                    .map(|r| format!("[{}]", r.join(", ")))
+                    .collect();
+                write!(f, "np.array([{}])", lines.join(",\n          "))
+            }
+        }
+    }
+}
+
+/// A matrix paired with a format, for use with `{}`.
+pub struct MatDisplay<'a, const M: usize, const N: usize> {
+    mat: &'a Mat<M, N>,
+    format: MatFormat,
+}
+
+impl<const M: usize, const N: usize> fmt::Display for MatDisplay<'_, M, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.format.write(self.mat, f)
+    }
+}
+
+impl<const M: usize, const N: usize> Mat<M, N> {
+    /// Display the matrix in the given format.
+    pub fn display(&self, format: MatFormat) -> MatDisplay<'_, M, N> {
+        MatDisplay { mat: self, format }
+    }
+}
+
+#[test]
+fn format_test() {
+    let A = mat![1, -2.5; 1234567, 0.0001];
+    let fmt = MatFormat::new().precision(2);
+    assert_eq!(
+        A.display(fmt).to_string(),
+        "    1.00    -2.50\n  1.23e6  1.00e-4"
+    );
+    let fmt = fmt.notation(Notation::Fixed).align(Align::Left);
+    assert_eq!(
+        A.display(fmt).to_string(),
+        "  1.00        -2.50\n  1234567.00  0.00 "
+    );
+    let fmt = MatFormat::new().precision(1).notation(Notation::Scientific);
+    assert_eq!(A.display(fmt).to_string(), "  1.0e0  -2.5e0\n  1.2e6  1.0e-4");
+}
+
+#[test]
+fn format_style_test() {
+    let A = mat![1, -2; 3e-9, R::INFINITY];
+    let fmt = MatFormat::new().precision(1);
+    assert_eq!(
+        A.display(fmt.style(Style::Latex)).to_string(),
+        "\\begin{bmatrix}\n  \
+         1.0 & -2.0 \\\\\n  \
+         3.0 \\times 10^{-9} & \\infty\n\
+         \\end{bmatrix}"
+    );
+    assert_eq!(
+        A.display(fmt.style(Style::Matlab)).to_string(),
+        "[   1.0  -2.0;\n 3.0e-9   Inf]"
+    );
+    assert_eq!(
+        A.display(fmt.style(Style::Numpy)).to_string(),
+        "np.array([[   1.0,   -2.0],\n          [3.0e-9, np.inf]])"
+    );
+
+    // MATLAB output reads back in.
+    let B = Mat::<4, 3>::rand();
+    let fmt = MatFormat::new().precision(17).notation(Notation::Scientific);
+    let text = B.display(fmt.style(Style::Matlab)).to_string();
+    assert_eq!(text.parse::<Mat<4, 3>>().unwrap().data, B.data);
+}
+
+#[test]
+fn format_truncate_test() {
+    let A = Mat::<5, 4>::from_fn(|i, j| (10 * i + j) as R);
+    let fmt = MatFormat::new().precision(0).truncate(3, 2);
+    assert_eq!(
+        A.display(fmt).to_string(),
+        "   11  ...   14\n   21  ...   24\n  ...  ...  ...\n   51  ...   54"
+    );
+    let fmt = fmt.style(Style::Latex);
+    assert_eq!(
+        A.display(fmt).to_string(),
+        "\\begin{bmatrix}\n  \
+         11 & \\cdots & 14 \\\\\n  \
+         21 & \\cdots & 24 \\\\\n  \
+         \\vdots & \\ddots & \\vdots \\\\\n  \
+         51 & \\cdots & 54\n\
+         \\end{bmatrix}"
+    );
+}
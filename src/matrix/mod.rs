@@ -1,15 +1,43 @@
 mod algebra;
 mod column_methods;
 mod core_traits;
+mod diagonal;
+mod ensembles;
+mod factorization;
+mod format;
+mod parse;
+mod permutation;
 mod scalar_traits;
+#[cfg(feature = "serde")]
+mod serialize;
+mod solve;
+mod sparse;
 mod square_matrix;
+mod symmetric;
+mod triangular;
 
+pub use diagonal::*;
+pub use factorization::*;
+pub use format::*;
+pub use permutation::*;
 pub use scalar_traits::*;
+pub use solve::*;
+pub use sparse::*;
+pub use symmetric::*;
+pub use triangular::*;
 
+use crate::io::text::TextFormat;
+use crate::io::{matrix_market, npy};
 use crate::na;
+use crate::parallel;
 use crate::prelude::*;
+use crate::random;
 
+use rand::Rng;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
 use std::ops::RangeInclusive;
+use std::path::Path;
 
 /// 1-indexed column-major matrix data structure.
 ///
@@ -33,14 +61,31 @@ impl<const M: usize, const N: usize> Mat<M, N> {
 
     /// Give a function that takes (row, col) as inputs, and returns
     /// the element to insert at that position
-    pub fn from_fn<F: Fn(usize, usize) -> R>(f: F) -> Self {
+    pub fn from_fn<F: FnMut(usize, usize) -> R>(mut f: F) -> Self {
         use std::array::from_fn as mk;
         Self { data: mk(|j| mk(|i| f(i + 1, j + 1))) }
     }
 
-    /// Generate a matrix populated with random values between 0 and 1.
+    /// Generate a matrix populated with random values between 0 and 1,
+    /// from the crate-wide generator (see `random::set_seed`).
     pub fn rand() -> Self {
-        Self::from_fn(|_, _| rand::random())
+        let mut A = Self::zero();
+        random::with_rng(|rng| A.fill_rand(rng));
+        A
+    }
+
+    /// Generate a matrix populated with random values between 0 and 1,
+    /// from the given generator.
+    pub fn rand_with<G: Rng + ?Sized>(rng: &mut G) -> Self {
+        let mut A = Self::zero();
+        A.fill_rand(rng);
+        A
+    }
+
+    /// Filled in place, as big matrices returned through closures
+    /// overflow the stack in debug builds.
+    fn fill_rand<G: Rng + ?Sized>(&mut self, rng: &mut G) {
+        self.data.iter_mut().flatten().for_each(|v| *v = rng.gen());
     }
 
     pub fn from(data: [[R; N]; M]) -> Self {
@@ -83,6 +128,21 @@ impl<const M: usize, const N: usize> Mat<M, N> {
         unsafe { std::mem::transmute(&mut self.data[j - 1]) }
     }
 
+    /// Apply `f(j, col)` to every column of the matrix. Columns are
+    /// split across threads when `parallel::set_num_threads` allows it.
+    pub fn for_each_col_mut<F>(&mut self, f: F)
+    where
+        F: Fn(usize, &mut Mat<M, 1>) + Sync,
+    {
+        parallel::for_each_chunk(&mut self.data, M, |j0, cols| {
+            for (j, col) in cols.iter_mut().enumerate() {
+                // SAFETY: Mat<M, 1> is a wrapper around [[R; M]; 1].
+                let col: &mut Mat<M, 1> = unsafe { std::mem::transmute(col) };
+                f(j0 + j + 1, col);
+            }
+        });
+    }
+
     /// Set the `i`-th row of the matrix.
     pub fn set_row(&mut self, i: usize, row: Mat<1, N>) {
         (1..=N).for_each(|j| self[(i, j)] = row[(1, j)]);
@@ -124,11 +184,13 @@ impl<const M: usize, const N: usize> Mat<M, N> {
     }
 
     /// Zeros-out entries to become upper triangular.
+    #[allow(clippy::wrong_self_convention)]
     pub fn to_upper_triangular(&mut self) {
         (1..=M).for_each(|i| (1..i).for_each(|j| self[(i, j)] = 0.));
     }
 
-    /// Zeros-out entries to become upper triangular.
+    /// Zeros-out entries to become lower triangular.
+    #[allow(clippy::wrong_self_convention)]
     pub fn to_lower_triangular(&mut self) {
         (1..=M).for_each(|i| (i + 1..=N).for_each(|j| self[(i, j)] = 0.));
     }
@@ -155,7 +217,12 @@ impl<const M: usize, const N: usize> Mat<M, N> {
 
     /// Swaps columns `a` and `b` in the matrix.
     pub fn swap_columns(&mut self, a: usize, b: usize) {
-        self.data.swap(a, b);
+        self.data.swap(a - 1, b - 1);
+    }
+
+    /// Swaps rows `a` and `b` in the matrix.
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        self.data.iter_mut().for_each(|col| col.swap(a - 1, b - 1));
     }
 
     /// Returns true if none of the entries of this matrix is NaN.
@@ -188,15 +255,65 @@ impl<const M: usize, const N: usize> Mat<M, N> {
         na::qr_decomp::householder(self)
     }
 
-    /// Solve linear-least-squares.
+    /// Execute a reduced QR decomposition via Householder reflections.
+    /// This requires M ≥ N.
+    pub fn qr_householder_reduced(&self) -> (Mat<M, N>, UpperTriangular<N>) {
+        na::qr_decomp::householder_reduced(self)
+    }
+
+    /// Solve linear-least-squares, for each column of B.
     /// Decomposes `self` into QR via householder, then applied backsub.
-    pub fn solve_lls(&self, b: &Mat<M, 1>) -> Mat<N, 1> {
-        let (mut Q, R) = self.qr_householder();
-        Q.transpose_inplace();
-        let v = Q * b;
-        let R1 = R.top_n_rows::<N>();
-        let v1 = v.top_n_rows::<N>();
-        R1.backward_sub(&v1)
+    pub fn solve_lls<const K: usize>(&self, B: &Mat<M, K>) -> Mat<N, K> {
+        let (Q, R) = self.qr_householder_reduced();
+        R.solve(&(Q.t() * B))
+    }
+
+    /// Read a matrix from a Matrix Market (.mtx) file. The size in the
+    /// file must match M×N.
+    pub fn read_matrix_market<P: AsRef<Path>>(path: P) -> Result<Self> {
+        matrix_market::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Write the matrix to a Matrix Market (.mtx) file.
+    pub fn write_matrix_market<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: matrix_market::Format,
+        symmetry: matrix_market::Symmetry,
+    ) -> Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        matrix_market::write(self, file, format, symmetry)
+    }
+
+    /// Read a matrix from a NumPy .npy file of float64, of shape (M, N).
+    pub fn read_npy<P: AsRef<Path>>(path: P) -> Result<Self> {
+        npy::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Write the matrix to a NumPy .npy file.
+    pub fn write_npy<P: AsRef<Path>>(
+        &self,
+        path: P,
+        order: npy::Order,
+    ) -> Result<()> {
+        npy::write(self, BufWriter::new(File::create(path)?), order)
+    }
+
+    /// Read a matrix from a delimited text file, such as a CSV file.
+    pub fn read_text<P: AsRef<Path>>(
+        path: P,
+        format: TextFormat,
+    ) -> Result<Self> {
+        format.read(BufReader::new(File::open(path)?))
+    }
+
+    /// Write the matrix to a delimited text file, one row per line.
+    pub fn write_text<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: TextFormat,
+    ) -> Result<()> {
+        format.write(self, BufWriter::new(File::create(path)?))
     }
 
     /// For column vectors, this gives the l1-norm or Manhattan
@@ -213,6 +330,15 @@ impl<const M: usize, const N: usize> Mat<M, N> {
         max
     }
 
+    /// For column vectors, this gives the max-norm, the largest absolute
+    /// value. For matrices, this gives the operator's l∞-norm, the
+    /// largest absolute row sum.
+    pub fn linf_norm(&self) -> R {
+        (1..=M)
+            .map(|i| (1..=N).map(|j| self[(i, j)].abs()).sum::<R>())
+            .fold(0., R::max)
+    }
+
     /// For column vectors, this gives the l2-norm or Euclidean norm.
     /// For matrices, this gives the Spectral norm, the square root of
     /// the largest eigenvalue of AᵀA.
@@ -230,6 +356,26 @@ impl<const M: usize, const N: usize> Mat<M, N> {
     }
 }
 
+/// Largest entry of |A - B|.
+#[cfg(test)]
+pub(crate) fn max_abs_diff<const M: usize, const N: usize>(
+    A: &Mat<M, N>,
+    B: &Mat<M, N>,
+) -> R {
+    let D = A - B;
+    D.data.iter().flatten().fold(0., |m, v| v.abs().max(m))
+}
+
+#[test]
+fn swap_test() {
+    let mut A = Mat::<2, 3>::from([[1., 2., 3.], [4., 5., 6.]]);
+    // 1-indexed, as everywhere else.
+    A.swap_columns(1, 3);
+    assert_eq!(A.data, Mat::from([[3., 2., 1.], [6., 5., 4.]]).data);
+    A.swap_rows(1, 2);
+    assert_eq!(A.data, Mat::from([[6., 5., 4.], [3., 2., 1.]]).data);
+}
+
 #[test]
 fn solve_qr_test() {
     for _ in 0..REPS {
@@ -237,6 +383,13 @@ fn solve_qr_test() {
         let b = Mat::<6, 1>::rand();
         let x = A.solve_lls(&b);
         let AT = A.transpose();
-        assert_eq_mat!(&AT * A * x, AT * b);
+        assert_eq_mat!(&AT * &A * x, &AT * &b);
+        // Each column of B is solved for on its own.
+        let B = Mat::<6, 3>::rand();
+        let X = A.solve_lls(&B);
+        for j in 1..=3 {
+            let x = A.solve_lls(B.col(j));
+            assert!((X.col(j) - x).l2_norm() < 1e-9);
+        }
     }
 }
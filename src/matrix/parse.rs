@@ -0,0 +1,108 @@
+// Runtime parsing of MATLAB-style matrix literals, e.g. "[1 2; 3 4]".
+
+use crate::prelude::*;
+
+use std::str::FromStr;
+
+/// Parses a matrix literal as MATLAB writes them: entries separated by
+/// commas or whitespace, rows by semicolons or newlines, optionally in
+/// square brackets, with a trailing `'` for the transpose.
+///
+/// ```ignore
+/// let x: Mat<3, 1> = "[1, 2, 3]'".parse()?;
+/// ```
+impl<const M: usize, const N: usize> FromStr for Mat<M, N> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (s, transpose) = match s.strip_suffix('\'') {
+            Some(s) => (s.trim_end(), true),
+            None => (s, false),
+        };
+        let s = match s.strip_prefix('[') {
+            Some(inner) => inner.strip_suffix(']').ok_or_else(|| {
+                Error::Parse(format!("unclosed `[` in `{s}`"))
+            })?,
+            None => s,
+        };
+
+        let mut rows: Vec<Vec<R>> = vec![];
+        for row in s.split([';', '\n']) {
+            let row = row
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|v| !v.is_empty())
+                .map(|v| {
+                    v.parse()
+                        .map_err(|_| Error::Parse(format!("bad number `{v}`")))
+                })
+                .collect::<Result<Vec<R>>>()?;
+            if row.is_empty() {
+                continue;
+            }
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(Error::Parse(format!(
+                        "row {} has {} entries, but row 1 has {}",
+                        rows.len() + 1,
+                        row.len(),
+                        first.len()
+                    )));
+                }
+            }
+            rows.push(row);
+        }
+
+        let (m, n) = (rows.len(), rows.first().map_or(0, |r| r.len()));
+        let found = if transpose { (n, m) } else { (m, n) };
+        if found != (M, N) {
+            return Err(Error::DimensionMismatch { expected: (M, N), found });
+        }
+        Ok(Self::from_fn(|i, j| match transpose {
+            true => rows[j - 1][i - 1],
+            false => rows[i - 1][j - 1],
+        }))
+    }
+}
+
+#[test]
+fn mat_macro_test() {
+    let A = mat![1, 2, 3; 4, 5, 6];
+    assert_eq!(A.data, [[1., 4.], [2., 5.], [3., 6.]]);
+    let x = mat![-2.; 0.5; 1e3];
+    assert_eq!(x.data, [[-2., 0.5, 1e3]]);
+    let y: R = 2.;
+    assert_eq!(mat![y, y * y].data, [[2.], [4.]]);
+}
+
+#[test]
+fn parse_test() {
+    let A: Mat<2, 2> = "[1 2; 3 4]".parse().unwrap();
+    assert_eq!(A.data, mat![1, 2; 3, 4].data);
+    let A: Mat<2, 3> = "1, 2,3\n -4 5e-1  inf".parse().unwrap();
+    assert_eq!(A.data, mat![1, 2, 3; -4, 0.5, R::INFINITY].data);
+    let A: Mat<2, 2> = "[1 2\n 3 4;\n]".parse().unwrap();
+    assert_eq!(A.data, mat![1, 2; 3, 4].data);
+
+    let x: Mat<3, 1> = "[1, 2, 3]'".parse().unwrap();
+    assert_eq!(x.data, mat![1; 2; 3].data);
+    let x: Mat<1, 3> = "[1; 2; 3]'".parse().unwrap();
+    assert_eq!(x.data, mat![1, 2, 3].data);
+    let A: Mat<3, 2> = "[1 2 3; 4 5 6]'".parse().unwrap();
+    assert_eq!(A.data, mat![1, 2, 3; 4, 5, 6].t().data);
+}
+
+#[test]
+fn parse_error_test() {
+    assert!(matches!(
+        "[1 2 3]".parse::<Mat<3, 1>>(),
+        Err(Error::DimensionMismatch { expected: (3, 1), found: (1, 3) })
+    ));
+    assert!(matches!(
+        "[]".parse::<Mat<1, 1>>(),
+        Err(Error::DimensionMismatch { found: (0, 0), .. })
+    ));
+    assert!(matches!("[1 2; 3]".parse::<Mat<2, 2>>(), Err(Error::Parse(_))));
+    assert!(matches!("[1 2; 3 4".parse::<Mat<2, 2>>(), Err(Error::Parse(_))));
+    assert!(matches!("[1 x]".parse::<Mat<1, 2>>(), Err(Error::Parse(_))));
+}
@@ -0,0 +1,176 @@
+use super::*;
+
+use std::fmt;
+use std::ops::Mul;
+
+/// N×N permutation matrix, stored as the row order it produces.
+///
+/// Row `i` of PA is row `perm[i]` of A, so P is the identity matrix
+/// with its rows taken in that order.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Permutation<const N: usize> {
+    perm: [usize; N],
+}
+
+impl<const N: usize> Permutation<N> {
+    /// Create a new identity permutation.
+    pub fn eye() -> Self {
+        Self { perm: std::array::from_fn(|i| i + 1) }
+    }
+
+    /// Build from a 1-indexed row order, checking that every row
+    /// appears exactly once.
+    pub fn new(perm: [usize; N]) -> Result<Self> {
+        let mut seen = [false; N];
+        for &p in &perm {
+            if p == 0 || p > N || seen[p - 1] {
+                return Err(Error::NotAPermutation);
+            }
+            seen[p - 1] = true;
+        }
+        Ok(Self { perm })
+    }
+
+    /// The row of the original matrix that ends up in row `i`.
+    pub fn row(&self, i: usize) -> usize {
+        self.perm[i - 1]
+    }
+
+    /// Swaps rows `a` and `b` of the permutation matrix.
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.perm.swap(a - 1, b - 1);
+    }
+
+    /// Inverse of a permutation matrix is its transpose.
+    pub fn inverse(&self) -> Self {
+        let mut perm = [0; N];
+        (1..=N).for_each(|i| perm[self.row(i) - 1] = i);
+        Self { perm }
+    }
+
+    /// (alias: inverse())
+    pub fn t(&self) -> Self {
+        self.inverse()
+    }
+
+    /// True if the permutation is a product of an even number of
+    /// transpositions.
+    pub fn is_even(&self) -> bool {
+        // A cycle of length k is made of k - 1 transpositions.
+        let mut seen = [false; N];
+        let mut transpositions = 0;
+        for start in 1..=N {
+            let (mut i, mut len) = (start, 0);
+            while !seen[i - 1] {
+                seen[i - 1] = true;
+                i = self.row(i);
+                len += 1;
+            }
+            transpositions += len.max(1) - 1;
+        }
+        transpositions % 2 == 0
+    }
+
+    /// Sign of the permutation: the determinant of the matrix.
+    pub fn sign(&self) -> R {
+        if self.is_even() {
+            1.
+        } else {
+            -1.
+        }
+    }
+
+    /// Expand into a dense matrix.
+    pub fn to_mat(&self) -> Mat<N, N> {
+        Mat::from_fn(|i, j| R::from(self.row(i) == j))
+    }
+}
+
+/// Permutes the rows of `rhs`.
+impl<const N: usize, const K: usize> Mul<&Mat<N, K>> for &Permutation<N> {
+    type Output = Mat<N, K>;
+    fn mul(self, rhs: &Mat<N, K>) -> Self::Output {
+        Mat::from_fn(|i, j| rhs[(self.row(i), j)])
+    }
+}
+
+/// Permutes the columns of `self`: column `perm[j]` of AP is column
+/// `j` of A.
+impl<const M: usize, const N: usize> Mul<&Permutation<N>> for &Mat<M, N> {
+    type Output = Mat<M, N>;
+    fn mul(self, rhs: &Permutation<N>) -> Self::Output {
+        let mut AP = Mat::zero();
+        (1..=N).for_each(|j| AP.data[rhs.row(j) - 1] = self.data[j - 1]);
+        AP
+    }
+}
+
+/// Composition: (PQ)A = P(QA).
+impl<const N: usize> Mul<&Permutation<N>> for &Permutation<N> {
+    type Output = Permutation<N>;
+    fn mul(self, rhs: &Permutation<N>) -> Self::Output {
+        Permutation { perm: std::array::from_fn(|i| rhs.row(self.perm[i])) }
+    }
+}
+
+impl<const N: usize> fmt::Display for Permutation<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_mat())
+    }
+}
+
+impl<const N: usize> fmt::Debug for Permutation<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Permutation {:?}", self.perm)
+    }
+}
+
+#[cfg(test)]
+fn rand_permutation<const N: usize>() -> Permutation<N> {
+    let mut P = Permutation::eye();
+    for i in 1..N {
+        let j = random::with_rng(|rng| rng.gen_range(i..=N));
+        P.swap(i, j);
+    }
+    P
+}
+
+#[test]
+fn permutation_new_test() {
+    assert!(Permutation::new([3, 1, 2]).is_ok());
+    assert!(Permutation::new([3, 1, 1]).is_err());
+    assert!(Permutation::new([0, 1, 2]).is_err());
+    assert!(Permutation::new([4, 1, 2]).is_err());
+}
+
+#[test]
+fn permutation_product_test() {
+    const N: usize = 6;
+    for _ in 0..REPS {
+        let (P, Q) = (rand_permutation::<N>(), rand_permutation::<N>());
+        let A = Mat::<N, 3>::rand();
+        let B = Mat::<3, N>::rand();
+        assert_eq_mat!(&P * &A, P.to_mat() * &A);
+        assert_eq_mat!(&B * &P, &B * P.to_mat());
+        assert_eq_mat!(&(&P * &Q) * &A, &P * &(&Q * &A));
+        assert_eq_mat!(&P.inverse() * &(&P * &A), A);
+        assert_eq!(&P * &P.t(), Permutation::eye());
+    }
+}
+
+#[test]
+fn permutation_sign_test() {
+    assert_eq!(Permutation::<4>::eye().sign(), 1.);
+    assert_eq!(Permutation::new([2, 1, 3, 4]).unwrap().sign(), -1.);
+    assert_eq!(Permutation::new([2, 3, 1, 4]).unwrap().sign(), 1.);
+    assert_eq!(Permutation::new([2, 1, 4, 3]).unwrap().sign(), 1.);
+    assert_eq!(Permutation::new([4, 1, 2, 3]).unwrap().sign(), -1.);
+    const N: usize = 5;
+    for _ in 0..SMALL_REPS {
+        let (P, Q) = (rand_permutation::<N>(), rand_permutation::<N>());
+        assert_eq!((&P * &Q).sign(), P.sign() * Q.sign());
+        // det(P) = sign(P), and PᵀP = I is pivoted into L = U = I.
+        let (Pt, _, U) = P.to_mat().lu_pivoted();
+        assert_eq!(Pt.sign() * U.det(), P.sign());
+    }
+}
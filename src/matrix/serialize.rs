@@ -0,0 +1,214 @@
+// serde support, behind the `serde` feature.
+//
+// A matrix is stored as `{ "rows": M, "cols": N, "data": [...] }`, with
+// `data` in column-major order like `Mat::data`. The structured matrix
+// types are stored as the dense matrix they represent (a diagonal matrix
+// as its diagonal, a permutation as its 1-based row indices), and their
+// structure is checked when they are read back.
+
+use super::*;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize)]
+struct MatRef<'a> {
+    rows: usize,
+    cols: usize,
+    data: &'a [R],
+}
+
+#[derive(Deserialize)]
+struct MatOwned {
+    rows: usize,
+    cols: usize,
+    data: Vec<R>,
+}
+
+impl<const M: usize, const N: usize> Serialize for Mat<M, N> {
+    fn serialize<S: Serializer>(
+        &self,
+        s: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let data = self.data.as_flattened();
+        MatRef { rows: M, cols: N, data }.serialize(s)
+    }
+}
+
+impl<'de, const M: usize, const N: usize> Deserialize<'de> for Mat<M, N> {
+    fn deserialize<D: Deserializer<'de>>(
+        d: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let MatOwned { rows, cols, data } = MatOwned::deserialize(d)?;
+        if (rows, cols) != (M, N) {
+            let msg = format!("expected a {M}×{N} matrix, found {rows}×{cols}");
+            return Err(D::Error::custom(msg));
+        }
+        if data.len() != M * N {
+            let msg = format!(
+                "a {M}×{N} matrix has {} entries, found {}",
+                M * N,
+                data.len()
+            );
+            return Err(D::Error::custom(msg));
+        }
+        Ok(Mat::from_fn(|i, j| data[(j - 1) * M + (i - 1)]))
+    }
+}
+
+impl<const N: usize> Serialize for UpperTriangular<N> {
+    fn serialize<S: Serializer>(
+        &self,
+        s: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        self.as_mat().serialize(s)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for UpperTriangular<N> {
+    fn deserialize<D: Deserializer<'de>>(
+        d: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let A = Mat::<N, N>::deserialize(d)?;
+        if (1..=N).any(|j| (j + 1..=N).any(|i| A[(i, j)] != 0.)) {
+            return Err(D::Error::custom("matrix is not upper triangular"));
+        }
+        Ok(A.into())
+    }
+}
+
+impl<const N: usize> Serialize for LowerTriangular<N> {
+    fn serialize<S: Serializer>(
+        &self,
+        s: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        self.as_mat().serialize(s)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for LowerTriangular<N> {
+    fn deserialize<D: Deserializer<'de>>(
+        d: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let A = Mat::<N, N>::deserialize(d)?;
+        if (1..=N).any(|j| (1..j).any(|i| A[(i, j)] != 0.)) {
+            return Err(D::Error::custom("matrix is not lower triangular"));
+        }
+        Ok(A.into())
+    }
+}
+
+impl<const N: usize> Serialize for Symmetric<N> {
+    fn serialize<S: Serializer>(
+        &self,
+        s: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        self.to_mat().serialize(s)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Symmetric<N> {
+    fn deserialize<D: Deserializer<'de>>(
+        d: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let A = Mat::<N, N>::deserialize(d)?;
+        Symmetric::from_mat(&A, 0.)
+            .map_err(|_| D::Error::custom("matrix is not symmetric"))
+    }
+}
+
+impl<const N: usize> Serialize for Diagonal<N> {
+    fn serialize<S: Serializer>(
+        &self,
+        s: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        self.diag().serialize(s)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Diagonal<N> {
+    fn deserialize<D: Deserializer<'de>>(
+        d: D,
+    ) -> std::result::Result<Self, D::Error> {
+        Ok(Mat::<N, 1>::deserialize(d)?.into())
+    }
+}
+
+impl<const N: usize> Serialize for Permutation<N> {
+    fn serialize<S: Serializer>(
+        &self,
+        s: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let rows: Vec<usize> = (1..=N).map(|i| self.row(i)).collect();
+        rows.serialize(s)
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Permutation<N> {
+    fn deserialize<D: Deserializer<'de>>(
+        d: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let rows = Vec::<usize>::deserialize(d)?;
+        let rows: [usize; N] = rows.try_into().map_err(|rows: Vec<_>| {
+            let msg = format!("expected {N} rows, found {}", rows.len());
+            D::Error::custom(msg)
+        })?;
+        Permutation::new(rows).map_err(|_| {
+            D::Error::custom(format!("{rows:?} is not a permutation"))
+        })
+    }
+}
+
+#[test]
+fn serde_mat_test() {
+    let A = mat![1, 2, 3; 4, 5, 6];
+    let json = serde_json::to_string(&A).unwrap();
+    assert_eq!(json, r#"{"rows":2,"cols":3,"data":[1.0,4.0,2.0,5.0,3.0,6.0]}"#);
+    let B: Mat<2, 3> = serde_json::from_str(&json).unwrap();
+    assert_eq!(A.data, B.data);
+
+    assert!(serde_json::from_str::<Mat<3, 2>>(&json).is_err());
+    let short = r#"{"rows":2,"cols":3,"data":[1.0,4.0,2.0,5.0,3.0]}"#;
+    assert!(serde_json::from_str::<Mat<2, 3>>(short).is_err());
+}
+
+#[test]
+fn serde_results_test() {
+    fn roundtrip<T: Serialize + for<'de> Deserialize<'de>>(x: &T) -> T {
+        serde_json::from_str(&serde_json::to_string(x).unwrap()).unwrap()
+    }
+
+    let A = Mat::<4, 3>::rand();
+    let (Q, R) = A.qr_householder_reduced();
+    let (Q2, R2) = roundtrip(&(Q.clone(), R.clone()));
+    assert_eq!(Q.data, Q2.data);
+    assert_eq!(R.as_mat().data, R2.as_mat().data);
+
+    let S = Symmetric::<4>::rand();
+    let (lambda, V) = S.eigen().unwrap();
+    let (lambda2, V2) = roundtrip(&(lambda.clone(), V.clone()));
+    assert_eq!(lambda.data, lambda2.data);
+    assert_eq!(V.data, V2.data);
+    assert_eq!(S.to_mat().data, roundtrip(&S).to_mat().data);
+
+    let (L, D) = S.ldlt();
+    assert_eq!(L.as_mat().data, roundtrip(&L).as_mat().data);
+    assert_eq!(D.diag().data, roundtrip(&D).diag().data);
+
+    let (P, L, U) = Mat::<4, 4>::rand().lu_pivoted();
+    assert!(roundtrip(&P) == P);
+    assert_eq!(L.as_mat().data, roundtrip(&L).as_mat().data);
+    assert_eq!(U.as_mat().data, roundtrip(&U).as_mat().data);
+}
+
+#[test]
+fn serde_structure_test() {
+    let A = mat![1, 2; 3, 4];
+    let json = serde_json::to_string(&A).unwrap();
+    assert!(serde_json::from_str::<UpperTriangular<2>>(&json).is_err());
+    assert!(serde_json::from_str::<LowerTriangular<2>>(&json).is_err());
+    assert!(serde_json::from_str::<Symmetric<2>>(&json).is_err());
+    assert!(serde_json::from_str::<Permutation<3>>("[1, 3, 3]").is_err());
+    assert!(serde_json::from_str::<Permutation<3>>("[1, 2]").is_err());
+    assert!(serde_json::from_str::<Permutation<3>>("[3, 1, 2]").is_ok());
+}
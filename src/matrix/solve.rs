@@ -0,0 +1,215 @@
+// One solve for all shapes and structures, like MATLAB's backslash: the
+// matrix is inspected, and the cheapest stable factorization for it is
+// used.
+
+use super::factorization::triangular_condition;
+use super::*;
+
+/// The factorization `Mat::solve` picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveMethod {
+    /// Backward substitution.
+    UpperTriangular,
+    /// Forward substitution.
+    LowerTriangular,
+    /// Cholesky, for symmetric matrices with a positive diagonal that
+    /// turn out to be positive definite.
+    Cholesky,
+    /// LU with partial pivoting, restricted to the band.
+    Banded { lower: usize, upper: usize },
+    /// LU with partial pivoting.
+    Lu,
+    /// Householder QR, for the least squares solution of an
+    /// overdetermined system.
+    LeastSquares,
+    /// Householder QR of Aᵀ, for the minimum norm solution of an
+    /// underdetermined system.
+    MinimumNorm,
+}
+
+/// The solution of Ax = b, and how it was found.
+#[derive(Debug, Clone)]
+pub struct Solution<const N: usize> {
+    pub x: Mat<N, 1>,
+    pub method: SolveMethod,
+    /// Estimate of the 1-norm condition number of A (of its R factor,
+    /// for the QR methods), which bounds the relative error of x at
+    /// about κ·ε.
+    pub condition: R,
+}
+
+impl<const M: usize, const N: usize> Mat<M, N> {
+    /// Number of nonzero diagonals (below, above) the main diagonal.
+    pub fn bandwidth(&self) -> (usize, usize) {
+        let (mut lower, mut upper) = (0, 0);
+        for j in 1..=N {
+            for i in (1..=M).filter(|&i| self[(i, j)] != 0.) {
+                lower = lower.max(i.saturating_sub(j));
+                upper = upper.max(j.saturating_sub(i));
+            }
+        }
+        (lower, upper)
+    }
+
+    /// Solve Ax = b, choosing the method by the shape and structure of
+    /// A, in this order:
+    ///
+    /// * triangular: substitution;
+    /// * symmetric with a positive diagonal: Cholesky, falling back to
+    ///   LU if A is not positive definite after all;
+    /// * banded, with the band under half the width: banded LU;
+    /// * any other square matrix: LU with partial pivoting;
+    /// * more rows than columns: least squares, by QR;
+    /// * fewer rows than columns: the minimum norm solution, by QR.
+    ///
+    /// Fails with `Error::Breakdown` if A is exactly singular (or rank
+    /// deficient). Check `condition` for nearly singular ones.
+    pub fn solve(&self, b: &Mat<M, 1>) -> Result<Solution<N>> {
+        use std::cmp::Ordering;
+        match M.cmp(&N) {
+            Ordering::Equal => {
+                // The same matrix, as the square type it is.
+                let A = Mat::<N, N>::from_fn(|i, j| self[(i, j)]);
+                let b = Mat::<N, 1>::from_fn(|i, _| b[i]);
+                solve_square(&A, &b)
+            }
+            Ordering::Greater => {
+                let qr = Qr::new(self);
+                if qr.is_singular() {
+                    return Err(Error::Breakdown);
+                }
+                let (x, condition) = (qr.solve(b), qr.condition());
+                Ok(Solution { x, method: SolveMethod::LeastSquares, condition })
+            }
+            Ordering::Less => {
+                // A = RᵀQᵀ, so x = QR⁻ᵀb is in the row space of A.
+                let qr = Qr::new(&self.t());
+                if qr.is_singular() {
+                    return Err(Error::Breakdown);
+                }
+                let (x, condition) = (qr.solve_transpose(b), qr.condition());
+                Ok(Solution { x, method: SolveMethod::MinimumNorm, condition })
+            }
+        }
+    }
+}
+
+fn solve_square<const N: usize>(
+    A: &Mat<N, N>,
+    b: &Mat<N, 1>,
+) -> Result<Solution<N>> {
+    let (lower, upper) = A.bandwidth();
+
+    if lower == 0 {
+        let U = UpperTriangular::from(A.clone());
+        let condition =
+            triangular_condition(A, |b| U.solve(b), |b| U.solve_transpose(b))?;
+        let method = SolveMethod::UpperTriangular;
+        return Ok(Solution { x: U.solve(b), method, condition });
+    }
+    if upper == 0 {
+        let L = LowerTriangular::from(A.clone());
+        let condition =
+            triangular_condition(A, |b| L.solve(b), |b| L.solve_transpose(b))?;
+        let method = SolveMethod::LowerTriangular;
+        return Ok(Solution { x: L.solve(b), method, condition });
+    }
+
+    let symmetric = A.data == A.t().data;
+    if symmetric && (1..=N).all(|i| A[(i, i)] > 0.) {
+        if let Ok(cholesky) = Cholesky::new(A) {
+            let (x, condition) = (cholesky.solve(b), cholesky.condition());
+            let method = SolveMethod::Cholesky;
+            return Ok(Solution { x, method, condition });
+        }
+    }
+
+    let (lu, method) = if lower + upper + 1 < N / 2 {
+        (Lu::banded(A, lower, upper), SolveMethod::Banded { lower, upper })
+    } else {
+        (Lu::new(A), SolveMethod::Lu)
+    };
+    if lu.is_singular() {
+        return Err(Error::Breakdown);
+    }
+    Ok(Solution { x: lu.solve(b), method, condition: lu.condition() })
+}
+
+#[cfg(test)]
+fn check_solution<const M: usize, const N: usize>(
+    A: &Mat<M, N>,
+    b: &Mat<M, 1>,
+    method: SolveMethod,
+) -> Solution<N> {
+    let solution = A.solve(b).unwrap();
+    assert_eq!(solution.method, method);
+    assert!(solution.condition >= 1.);
+    if M <= N {
+        assert!((b - A * &solution.x).l2_norm() < 1e-9 * b.l2_norm());
+    }
+    solution
+}
+
+#[test]
+fn solve_dispatch_test() {
+    const N: usize = 10;
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::rand();
+        let b = Mat::<N, 1>::rand();
+        let mut U = A.upper_triangular();
+        U.add_identity(1.);
+        check_solution(&U, &b, SolveMethod::UpperTriangular);
+        check_solution(&U.t(), &b, SolveMethod::LowerTriangular);
+        check_solution(
+            &Mat::<N, N>::symmetric_positive_definite(),
+            &b,
+            SolveMethod::Cholesky,
+        );
+        let mut T = Mat::<N, N>::tridiagonal();
+        T.add_identity(1.);
+        check_solution(&T, &b, SolveMethod::Banded { lower: 1, upper: 1 });
+        check_solution(&A, &b, SolveMethod::Lu);
+        // Symmetric with a positive diagonal, but indefinite.
+        let mut S = Mat::<N, N>::symmetric();
+        (1..=N).for_each(|i| S[(i, i)] = 0.1);
+        S[(1, 2)] = 10.;
+        S[(2, 1)] = 10.;
+        check_solution(&S, &b, SolveMethod::Lu);
+    }
+}
+
+#[test]
+fn solve_rectangular_test() {
+    for _ in 0..SMALL_REPS {
+        // Overdetermined: the residual is orthogonal to the columns.
+        let A = Mat::<8, 3>::rand();
+        let b = Mat::<8, 1>::rand();
+        let x = check_solution(&A, &b, SolveMethod::LeastSquares).x;
+        let r = &b - &A * &x;
+        assert!((A.t() * &r).l2_norm() < 1e-9);
+        assert_eq_mat!(x, A.solve_lls(&b), 1e-6);
+        // Underdetermined: x is in the row space, so x = Aᵀy.
+        let A = Mat::<3, 8>::rand();
+        let b = Mat::<3, 1>::rand();
+        let x = check_solution(&A, &b, SolveMethod::MinimumNorm).x;
+        let AAt = &A * A.t();
+        let y = AAt.solve(&b).unwrap().x;
+        assert!((&x - A.t() * &y).l2_norm() < 1e-9);
+    }
+}
+
+#[test]
+fn solve_condition_test() {
+    // Hilbert matrices are famously ill conditioned.
+    let A = crate::gallery::hilbert::<6>();
+    let solution = A.solve(&Mat::from_fn(|_, _| 1.)).unwrap();
+    assert_eq!(solution.method, SolveMethod::Cholesky);
+    let exact = A.l1_norm() * crate::gallery::hilbert_inverse::<6>().l1_norm();
+    assert!(solution.condition <= exact * (1. + 1e-6));
+    assert!(solution.condition >= exact / 3.);
+
+    let singular = mat![1, 2; 2, 4];
+    assert!(matches!(singular.solve(&mat![1; 1]), Err(Error::Breakdown)));
+    let singular = mat![1, 2; 0, 0];
+    assert!(matches!(singular.solve(&mat![1; 1]), Err(Error::Breakdown)));
+}
@@ -0,0 +1,173 @@
+use super::*;
+
+use std::fmt;
+use std::ops::{Index, Mul};
+
+/// M×N sparse matrix in compressed sparse row (CSR) storage.
+///
+/// Only the nonzero pattern is stored, so it can stand in for matrices
+/// far too big for a dense `Mat`, such as 2D Laplacians.
+#[derive(Clone, PartialEq)]
+pub struct Sparse<const M: usize, const N: usize> {
+    /// The entries of row i are at `row_ptr[i - 1]..row_ptr[i]`.
+    row_ptr: Vec<usize>,
+    /// Column of each entry, ascending within each row.
+    cols: Vec<usize>,
+    values: Vec<R>,
+}
+
+impl<const M: usize, const N: usize> Sparse<M, N> {
+    /// Matrix of zeros, with no stored entries.
+    pub fn zero() -> Self {
+        Self { row_ptr: vec![0; M + 1], cols: vec![], values: vec![] }
+    }
+
+    /// Build from (row, col, value) triplets, in any order. Values at
+    /// the same position are summed.
+    pub fn from_triplets(entries: &[(usize, usize, R)]) -> Self {
+        let mut entries = entries.to_vec();
+        entries.sort_by_key(|&(i, j, _)| (i, j));
+        let mut A = Self::zero();
+        let mut last = (0, 0);
+        for (i, j, v) in entries {
+            assert!(
+                (1..=M).contains(&i) && (1..=N).contains(&j),
+                "({i}, {j}) is out of range for a {M}×{N} matrix"
+            );
+            if (i, j) == last {
+                *A.values.last_mut().unwrap() += v;
+                continue;
+            }
+            A.cols.push(j);
+            A.values.push(v);
+            A.row_ptr[i] += 1;
+            last = (i, j);
+        }
+        // Row lengths to row ends.
+        for i in 1..=M {
+            A.row_ptr[i] += A.row_ptr[i - 1];
+        }
+        A
+    }
+
+    /// The nonzero entries of a dense matrix.
+    pub fn from_mat(A: &Mat<M, N>) -> Self {
+        let mut S = Self::zero();
+        for i in 1..=M {
+            for j in (1..=N).filter(|&j| A[(i, j)] != 0.) {
+                S.cols.push(j);
+                S.values.push(A[(i, j)]);
+            }
+            S.row_ptr[i] = S.cols.len();
+        }
+        S
+    }
+
+    pub fn to_mat(&self) -> Mat<M, N> {
+        let mut A = Mat::zero();
+        for i in 1..=M {
+            self.row(i).for_each(|(j, v)| A[(i, j)] = v);
+        }
+        A
+    }
+
+    /// Number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The stored entries of row `i`, as (col, value) pairs.
+    pub fn row(&self, i: usize) -> impl Iterator<Item = (usize, R)> + '_ {
+        let range = self.row_ptr[i - 1]..self.row_ptr[i];
+        self.cols[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.values[range].iter().copied())
+    }
+
+    /// Position of the entry (i, j) in `values`, if it is stored.
+    fn position(&self, i: usize, j: usize) -> Option<usize> {
+        let start = self.row_ptr[i - 1];
+        let cols = &self.cols[start..self.row_ptr[i]];
+        cols.binary_search(&j).ok().map(|k| start + k)
+    }
+
+    pub fn transpose(&self) -> Sparse<N, M> {
+        let mut entries = Vec::with_capacity(self.nnz());
+        for i in 1..=M {
+            entries.extend(self.row(i).map(|(j, v)| (j, i, v)));
+        }
+        Sparse::from_triplets(&entries)
+    }
+
+    /// (alias: transpose())
+    pub fn t(&self) -> Sparse<N, M> {
+        self.transpose()
+    }
+}
+
+impl<const M: usize, const N: usize> Index<(usize, usize)> for Sparse<M, N> {
+    type Output = R;
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        match self.position(i, j) {
+            Some(k) => &self.values[k],
+            None => &0.,
+        }
+    }
+}
+
+/// Sparse-dense product, in O(nnz·K).
+impl<const M: usize, const N: usize, const K: usize> Mul<&Mat<N, K>>
+    for &Sparse<M, N>
+{
+    type Output = Mat<M, K>;
+    fn mul(self, rhs: &Mat<N, K>) -> Self::Output {
+        let mut Y = Mat::<M, K>::zero();
+        for c in 1..=K {
+            for i in 1..=M {
+                Y[(i, c)] = self.row(i).map(|(j, v)| v * rhs[(j, c)]).sum();
+            }
+        }
+        Y
+    }
+}
+
+impl<const M: usize, const N: usize> fmt::Debug for Sparse<M, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Sparse {M}×{N}, {} entries", self.nnz())?;
+        for i in 1..=M {
+            for (j, v) in self.row(i) {
+                write!(f, "\n  ({i}, {j}) {v}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn sparse_triplets_test() {
+    let A = Sparse::<3, 4>::from_triplets(&[
+        (3, 1, 5.),
+        (1, 4, 1.),
+        (1, 2, 2.),
+        (3, 1, -1.),
+    ]);
+    assert_eq!(A.nnz(), 3);
+    assert_eq!(A.to_mat().data, mat![0, 2, 0, 1; 0, 0, 0, 0; 4, 0, 0, 0].data);
+    assert_eq!((A[(1, 2)], A[(3, 1)], A[(2, 2)]), (2., 4., 0.));
+    assert_eq!(A.row(1).collect::<Vec<_>>(), [(2, 2.), (4, 1.)]);
+    assert_eq!(A.t().to_mat().data, A.to_mat().t().data);
+}
+
+#[test]
+fn sparse_product_test() {
+    for _ in 0..SMALL_REPS {
+        let mut A = Mat::<6, 5>::rand();
+        (1..=6).for_each(|i| A[(i, (i % 5) + 1)] = 0.);
+        let S = Sparse::from_mat(&A);
+        assert_eq!(S.nnz(), 6 * 5 - 6);
+        assert_eq!(S.to_mat().data, A.data);
+        let X = Mat::<5, 3>::rand();
+        assert_eq_mat!(&S * &X, &A * &X, 1e-12);
+    }
+}
@@ -1,20 +1,33 @@
 use super::*;
 
 impl<const N: usize> Mat<N, N> {
-    /// Apply backward substitution on Ax = b, with A := self.
-    pub fn backward_sub(&self, b: &Mat<N, 1>) -> Mat<N, 1> {
-        assert!(
-            self.is_upper_triangular(),
-            "A needs to be upper-triangular:\n{self}"
-        );
-        let mut x = b.clone();
-        for k in (1..=N).rev() {
-            for j in k + 1..=N {
-                x[k] -= self[(k, j)] * x[j];
-            }
-            x[k] /= self[(k, k)];
-        }
-        x
+    /// Cholesky factor L, where `self` = LLᵀ.
+    ///
+    /// `self` MUST be symmetric positive definite.
+    pub fn cholesky(&self) -> LowerTriangular<N> {
+        let mut L = self.clone();
+        na::cholesky(&mut L);
+        L.into()
+    }
+
+    /// LU factorization without pivoting, where `self` = LU and L has
+    /// a unit diagonal.
+    pub fn lu(&self) -> (LowerTriangular<N>, UpperTriangular<N>) {
+        let mut A = self.clone();
+        na::lu(&mut A);
+        let L = Mat::from_fn(|i, j| if i == j { 1. } else { A[(i, j)] });
+        (L.into(), A.into())
+    }
+
+    /// LU factorization with partial pivoting, where P`self` = LU and
+    /// L has a unit diagonal.
+    pub fn lu_pivoted(
+        &self,
+    ) -> (Permutation<N>, LowerTriangular<N>, UpperTriangular<N>) {
+        let mut A = self.clone();
+        let P = na::lu_partial_pivot(&mut A);
+        let L = Mat::from_fn(|i, j| if i == j { 1. } else { A[(i, j)] });
+        (P, L.into(), A.into())
     }
 
     /// Tranpose in-place; possible since it's a square.
@@ -46,12 +59,41 @@ impl<const N: usize> Mat<N, N> {
 
     /// Create a random symmetric matrix.
     pub fn symmetric() -> Self {
-        let x = Mat::rand();
+        random::with_rng(Self::symmetric_with)
+    }
+
+    /// Create a random symmetric matrix from the given generator.
+    pub fn symmetric_with<G: Rng + ?Sized>(rng: &mut G) -> Self {
+        let x = Mat::rand_with(rng);
         (&x + x.transpose()) / 2.
     }
 
-    /// Create a random symmetric positive definite matrix.
+    /// Create a random symmetric positive definite matrix. Adding N·I
+    /// makes it diagonally dominant, as in matlab/+random.
     pub fn symmetric_positive_definite() -> Self {
-        Mat::symmetric() + Mat::eye()
+        random::with_rng(Self::symmetric_positive_definite_with)
+    }
+
+    /// Create a random symmetric positive definite matrix from the given
+    /// generator.
+    pub fn symmetric_positive_definite_with<G: Rng + ?Sized>(
+        rng: &mut G,
+    ) -> Self {
+        let mut A = Mat::symmetric_with(rng);
+        A.add_identity(N as R);
+        A
+    }
+
+    /// Create a random (nonsymmetric) positive definite matrix: xᵀAx > 0
+    /// for all x ≠ 0.
+    pub fn positive_definite() -> Self {
+        random::with_rng(Self::positive_definite_with)
+    }
+
+    /// Create a random positive definite matrix from the given generator.
+    pub fn positive_definite_with<G: Rng + ?Sized>(rng: &mut G) -> Self {
+        let mut A = Mat::rand_with(rng);
+        A.add_identity(N as R);
+        A
     }
 }
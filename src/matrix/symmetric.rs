@@ -0,0 +1,235 @@
+use super::*;
+
+use std::fmt;
+use std::ops::{Index, IndexMut, Mul};
+
+/// Symmetric N×N matrix in packed storage.
+///
+/// Only the lower triangle is stored, column by column, so it takes
+/// N(N+1)/2 entries instead of N². Indexing (i, j) and (j, i) refers
+/// to the same entry.
+#[derive(Clone)]
+pub struct Symmetric<const N: usize> {
+    data: Vec<R>,
+}
+
+/// Position of (i, j) in the packed lower triangle, with i ≥ j.
+fn packed_index<const N: usize>(i: usize, j: usize) -> usize {
+    let (i, j) = if i >= j { (i, j) } else { (j, i) };
+    (j - 1) * (2 * N + 2 - j) / 2 + (i - j)
+}
+
+impl<const N: usize> Symmetric<N> {
+    /// Symmetric matrix of zeros.
+    pub fn zero() -> Self {
+        Self { data: vec![0.; N * (N + 1) / 2] }
+    }
+
+    /// Give a function that takes (row, col) as inputs, and returns
+    /// the element to insert at that position. Only called with
+    /// row ≥ col.
+    pub fn from_fn<F: FnMut(usize, usize) -> R>(mut f: F) -> Self {
+        let mut S = Self::zero();
+        for j in 1..=N {
+            (j..=N).for_each(|i| S[(i, j)] = f(i, j));
+        }
+        S
+    }
+
+    /// Create a random symmetric matrix.
+    pub fn rand() -> Self {
+        random::with_rng(Self::rand_with)
+    }
+
+    /// Create a random symmetric matrix from the given generator.
+    pub fn rand_with<G: Rng + ?Sized>(rng: &mut G) -> Self {
+        Self::from_fn(|_, _| rng.gen())
+    }
+
+    /// Pack a dense matrix, checking that it is symmetric up to a
+    /// relative tolerance, ‖A - Aᵀ‖₁ ≤ `rel_tol`·‖A‖₁. The two halves
+    /// are averaged.
+    pub fn from_mat(A: &Mat<N, N>, rel_tol: R) -> Result<Self> {
+        if (A - A.t()).l1_norm() > rel_tol * A.l1_norm() {
+            return Err(Error::NotSymmetric);
+        }
+        Ok(Self::from_fn(|i, j| (A[(i, j)] + A[(j, i)]) / 2.))
+    }
+
+    /// Unpack into a dense matrix.
+    pub fn to_mat(&self) -> Mat<N, N> {
+        Mat::from_fn(|i, j| self[(i, j)])
+    }
+
+    /// Trace: sum of elements on the diagonal
+    pub fn trace(&self) -> R {
+        (1..=N).map(|i| self[(i, i)]).sum()
+    }
+
+    /// Adds `lambda` * `I` to `self`.
+    pub fn add_identity(&mut self, lambda: R) {
+        (1..=N).for_each(|i| self[(i, i)] += lambda);
+    }
+
+    /// Symmetric rank-1 update: `self` += αxxᵀ.
+    pub fn rank1_update(&mut self, alpha: R, x: &Mat<N, 1>) {
+        for j in 1..=N {
+            let ax = alpha * x[j];
+            (j..=N).for_each(|i| self[(i, j)] += ax * x[i]);
+        }
+    }
+
+    /// Symmetric rank-2 update: `self` += α(xyᵀ + yxᵀ).
+    pub fn rank2_update(&mut self, alpha: R, x: &Mat<N, 1>, y: &Mat<N, 1>) {
+        for j in 1..=N {
+            let (ax, ay) = (alpha * x[j], alpha * y[j]);
+            (j..=N).for_each(|i| self[(i, j)] += x[i] * ay + y[i] * ax);
+        }
+    }
+
+    /// Cholesky factor L, where `self` = LLᵀ.
+    ///
+    /// `self` MUST be positive definite.
+    pub fn cholesky(&self) -> LowerTriangular<N> {
+        let mut L = self.clone();
+        na::cholesky_packed(&mut L);
+        Mat::from_fn(|i, j| if i >= j { L[(i, j)] } else { 0. }).into()
+    }
+
+    /// LDLᵀ factorization without pivoting. Returns the unit
+    /// lower-triangular L and D.
+    pub fn ldlt(&self) -> (LowerTriangular<N>, Diagonal<N>) {
+        let mut A = self.clone();
+        na::ldlt(&mut A);
+        let L = Mat::from_fn(|i, j| match i.cmp(&j) {
+            std::cmp::Ordering::Greater => A[(i, j)],
+            std::cmp::Ordering::Equal => 1.,
+            std::cmp::Ordering::Less => 0.,
+        });
+        (L.into(), Mat::from_fn(|i, _| A[(i, i)]).into())
+    }
+
+    /// All eigenvalues (ascending) and their orthonormal eigenvectors.
+    pub fn eigen(&self) -> Result<(Mat<N, 1>, Mat<N, N>)> {
+        na::symmetric_eigen(self)
+    }
+}
+
+impl<const N: usize> Index<(usize, usize)> for Symmetric<N> {
+    type Output = R;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.data[packed_index::<N>(index.0, index.1)]
+    }
+}
+
+/// Writing to (i, j) also writes to (j, i).
+impl<const N: usize> IndexMut<(usize, usize)> for Symmetric<N> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        &mut self.data[packed_index::<N>(index.0, index.1)]
+    }
+}
+
+/// Symmetric matrix-matrix product, reading each packed entry once
+/// per column of `rhs`.
+impl<const N: usize, const K: usize> Mul<&Mat<N, K>> for &Symmetric<N> {
+    type Output = Mat<N, K>;
+    fn mul(self, rhs: &Mat<N, K>) -> Self::Output {
+        let mut Y = Mat::<N, K>::zero();
+        for c in 1..=K {
+            for j in 1..=N {
+                let xj = rhs[(j, c)];
+                Y[(j, c)] += self[(j, j)] * xj;
+                for i in j + 1..=N {
+                    let a = self[(i, j)];
+                    Y[(i, c)] += a * xj;
+                    Y[(j, c)] += a * rhs[(i, c)];
+                }
+            }
+        }
+        Y
+    }
+}
+
+impl<const N: usize> fmt::Display for Symmetric<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_mat())
+    }
+}
+
+impl<const N: usize> fmt::Debug for Symmetric<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.to_mat())
+    }
+}
+
+#[cfg(test)]
+fn rand_spd<const N: usize>() -> Symmetric<N> {
+    let mut S = Symmetric::rand();
+    S.add_identity(N as R);
+    S
+}
+
+#[test]
+fn symmetric_packing_test() {
+    const N: usize = 5;
+    let S = Symmetric::<N>::rand();
+    let A = S.to_mat();
+    assert_eq!(S.data.len(), N * (N + 1) / 2);
+    assert_eq_mat!(A, A.t());
+    assert_eq_mat!(Symmetric::from_mat(&A, 1e-12).unwrap().to_mat(), A);
+    assert!(Symmetric::from_mat(&Mat::<N, N>::rand(), 1e-6).is_err());
+    // Rounding errors in entries that should be 0, as in a computed
+    // AᵀA, are small next to A.
+    let mut B = A.clone();
+    B[(1, 2)] = 1e-17;
+    B[(2, 1)] = -1e-17;
+    let S = Symmetric::from_mat(&B, 1e-12).unwrap();
+    assert_eq!(S[(1, 2)], 0.);
+}
+
+#[test]
+fn symmetric_product_test() {
+    const N: usize = 6;
+    for _ in 0..REPS {
+        let S = Symmetric::<N>::rand();
+        let B = Mat::<N, 3>::rand();
+        assert_eq_mat!(&S * &B, S.to_mat() * &B);
+    }
+}
+
+#[test]
+fn symmetric_rank_update_test() {
+    const N: usize = 6;
+    for _ in 0..SMALL_REPS {
+        let mut S = Symmetric::<N>::rand();
+        let (x, y) = (Mat::<N, 1>::rand(), Mat::<N, 1>::rand());
+        let (xt, yt) = (x.t(), y.t());
+        let A = S.to_mat();
+        S.rank1_update(0.5, &x);
+        assert_eq_mat!(S.to_mat(), &A + 0.5 * &x * &xt);
+        S.rank2_update(-2., &x, &y);
+        let expected = A + 0.5 * &x * &xt - 2. * (&x * &yt + &y * &xt);
+        assert_eq_mat!(S.to_mat(), expected, 1e-4);
+    }
+}
+
+#[test]
+fn symmetric_cholesky_test() {
+    const N: usize = 6;
+    for _ in 0..REPS {
+        let S = rand_spd::<N>();
+        let L = S.cholesky();
+        assert_eq_mat!(&L * L.t().as_mat(), S.to_mat());
+        assert_eq_mat!(L.into_mat(), S.to_mat().cholesky().into_mat());
+    }
+}
+
+#[test]
+fn symmetric_ldlt_test() {
+    const N: usize = 6;
+    for _ in 0..REPS {
+        let S = rand_spd::<N>();
+        let (L, D) = S.ldlt();
+        assert_eq_mat!(&L * &(&D * L.t().as_mat()), S.to_mat());
+    }
+}
@@ -0,0 +1,271 @@
+use super::*;
+
+use std::fmt;
+use std::ops::{Index, Mul};
+
+/// Upper-triangular N×N matrix.
+///
+/// Entries below the diagonal are zero by construction, so solves,
+/// products and inverses only ever touch the upper half.
+#[derive(Clone)]
+pub struct UpperTriangular<const N: usize> {
+    mat: Mat<N, N>,
+}
+
+/// Lower-triangular N×N matrix.
+///
+/// Entries above the diagonal are zero by construction, so solves,
+/// products and inverses only ever touch the lower half.
+#[derive(Clone)]
+pub struct LowerTriangular<const N: usize> {
+    mat: Mat<N, N>,
+}
+
+/// Takes the upper-triangular portion of the matrix.
+impl<const N: usize> From<Mat<N, N>> for UpperTriangular<N> {
+    fn from(mut mat: Mat<N, N>) -> Self {
+        mat.to_upper_triangular();
+        Self { mat }
+    }
+}
+
+/// Takes the lower-triangular portion of the matrix.
+impl<const N: usize> From<Mat<N, N>> for LowerTriangular<N> {
+    fn from(mut mat: Mat<N, N>) -> Self {
+        mat.to_lower_triangular();
+        Self { mat }
+    }
+}
+
+impl<const N: usize> UpperTriangular<N> {
+    /// View as a dense matrix.
+    pub fn as_mat(&self) -> &Mat<N, N> {
+        &self.mat
+    }
+
+    /// Convert into a dense matrix.
+    pub fn into_mat(self) -> Mat<N, N> {
+        self.mat
+    }
+
+    pub fn transpose(&self) -> LowerTriangular<N> {
+        LowerTriangular { mat: self.mat.transpose() }
+    }
+
+    /// (alias: tranpose())
+    pub fn t(&self) -> LowerTriangular<N> {
+        self.transpose()
+    }
+
+    /// Determinant: product of elements on the diagonal.
+    pub fn det(&self) -> R {
+        (1..=N).map(|i| self[(i, i)]).product()
+    }
+
+    /// Solve UX = B, with U := self, via backward substitution.
+    pub fn solve<const K: usize>(&self, B: &Mat<N, K>) -> Mat<N, K> {
+        na::backward_sub(self, B)
+    }
+
+    /// Solve UᵀX = B, without forming Uᵀ.
+    pub fn solve_transpose<const K: usize>(&self, B: &Mat<N, K>) -> Mat<N, K> {
+        use na::{Diag, Transpose, Triangle};
+        na::trsm(&self.mat, B, Triangle::Upper, Transpose::Yes, Diag::NonUnit)
+    }
+
+    /// Inverse of an upper-triangular matrix is upper-triangular.
+    pub fn inverse(&self) -> Self {
+        let mut X = Mat::<N, N>::zero();
+        for j in 1..=N {
+            X[(j, j)] = self[(j, j)].recip();
+            for i in (1..j).rev() {
+                let s: R = (i + 1..=j).map(|k| self[(i, k)] * X[(k, j)]).sum();
+                X[(i, j)] = -s / self[(i, i)];
+            }
+        }
+        Self { mat: X }
+    }
+}
+
+impl<const N: usize> LowerTriangular<N> {
+    /// View as a dense matrix.
+    pub fn as_mat(&self) -> &Mat<N, N> {
+        &self.mat
+    }
+
+    /// Convert into a dense matrix.
+    pub fn into_mat(self) -> Mat<N, N> {
+        self.mat
+    }
+
+    pub fn transpose(&self) -> UpperTriangular<N> {
+        UpperTriangular { mat: self.mat.transpose() }
+    }
+
+    /// (alias: tranpose())
+    pub fn t(&self) -> UpperTriangular<N> {
+        self.transpose()
+    }
+
+    /// Determinant: product of elements on the diagonal.
+    pub fn det(&self) -> R {
+        (1..=N).map(|i| self[(i, i)]).product()
+    }
+
+    /// Solve LX = B, with L := self, via forward substitution.
+    pub fn solve<const K: usize>(&self, B: &Mat<N, K>) -> Mat<N, K> {
+        na::forward_sub(self, B)
+    }
+
+    /// Solve LᵀX = B, without forming Lᵀ.
+    pub fn solve_transpose<const K: usize>(&self, B: &Mat<N, K>) -> Mat<N, K> {
+        use na::{Diag, Transpose, Triangle};
+        na::trsm(&self.mat, B, Triangle::Lower, Transpose::Yes, Diag::NonUnit)
+    }
+
+    /// Inverse of a lower-triangular matrix is lower-triangular.
+    pub fn inverse(&self) -> Self {
+        let mut X = Mat::<N, N>::zero();
+        for j in 1..=N {
+            X[(j, j)] = self[(j, j)].recip();
+            for i in j + 1..=N {
+                let s: R = (j..i).map(|k| self[(i, k)] * X[(k, j)]).sum();
+                X[(i, j)] = -s / self[(i, i)];
+            }
+        }
+        Self { mat: X }
+    }
+}
+
+impl<const N: usize> Index<(usize, usize)> for UpperTriangular<N> {
+    type Output = R;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.mat[index]
+    }
+}
+
+impl<const N: usize> Index<(usize, usize)> for LowerTriangular<N> {
+    type Output = R;
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.mat[index]
+    }
+}
+
+/// Skips the zeros below the diagonal: row `i` starts at column `i`.
+impl<const N: usize, const K: usize> Mul<&Mat<N, K>> for &UpperTriangular<N> {
+    type Output = Mat<N, K>;
+    fn mul(self, rhs: &Mat<N, K>) -> Self::Output {
+        Mat::from_fn(|i, j| (i..=N).map(|k| self[(i, k)] * rhs[(k, j)]).sum())
+    }
+}
+
+/// Skips the zeros above the diagonal: row `i` ends at column `i`.
+impl<const N: usize, const K: usize> Mul<&Mat<N, K>> for &LowerTriangular<N> {
+    type Output = Mat<N, K>;
+    fn mul(self, rhs: &Mat<N, K>) -> Self::Output {
+        Mat::from_fn(|i, j| (1..=i).map(|k| self[(i, k)] * rhs[(k, j)]).sum())
+    }
+}
+
+/// Product of upper-triangular matrices is upper-triangular.
+impl<const N: usize> Mul<&UpperTriangular<N>> for &UpperTriangular<N> {
+    type Output = UpperTriangular<N>;
+    fn mul(self, rhs: &UpperTriangular<N>) -> Self::Output {
+        let x = |i, j| (i..=j).map(|k| self[(i, k)] * rhs[(k, j)]).sum();
+        UpperTriangular { mat: Mat::from_fn(x) }
+    }
+}
+
+/// Product of lower-triangular matrices is lower-triangular.
+impl<const N: usize> Mul<&LowerTriangular<N>> for &LowerTriangular<N> {
+    type Output = LowerTriangular<N>;
+    fn mul(self, rhs: &LowerTriangular<N>) -> Self::Output {
+        let x = |i, j| (j..=i).map(|k| self[(i, k)] * rhs[(k, j)]).sum();
+        LowerTriangular { mat: Mat::from_fn(x) }
+    }
+}
+
+impl<const N: usize> fmt::Display for UpperTriangular<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.mat)
+    }
+}
+
+impl<const N: usize> fmt::Debug for UpperTriangular<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.mat)
+    }
+}
+
+impl<const N: usize> fmt::Display for LowerTriangular<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.mat)
+    }
+}
+
+impl<const N: usize> fmt::Debug for LowerTriangular<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.mat)
+    }
+}
+
+#[cfg(test)]
+fn rand_upper<const N: usize>() -> UpperTriangular<N> {
+    let mut A = Mat::<N, N>::rand();
+    A.add_identity(1.); // keep away from singular.
+    A.into()
+}
+
+#[test]
+fn triangular_solve_test() {
+    const N: usize = 6;
+    for _ in 0..REPS {
+        let U = rand_upper::<N>();
+        let L = rand_upper::<N>().t();
+        let b = Mat::<N, 1>::rand();
+        assert_eq_mat!(&U * &U.solve(&b), b);
+        assert_eq_mat!(&L * &L.solve(&b), b);
+    }
+}
+
+#[test]
+fn triangular_inverse_test() {
+    const N: usize = 6;
+    for _ in 0..SMALL_REPS {
+        let U = rand_upper::<N>();
+        let L = rand_upper::<N>().t();
+        let b = Mat::<N, 1>::rand();
+        assert_eq_mat!(&U.inverse() * &b, U.solve(&b), 1e-4);
+        assert_eq_mat!(&L.inverse() * &b, L.solve(&b), 1e-4);
+    }
+}
+
+#[test]
+fn triangular_solve_many_test() {
+    const N: usize = 6;
+    for _ in 0..SMALL_REPS {
+        let U = rand_upper::<N>();
+        let L = rand_upper::<N>().t();
+        let B = Mat::<N, 3>::rand();
+        // Solving for the identity computes the inverse.
+        let I = Mat::<N, N>::eye();
+        assert!((U.solve(&I) - U.inverse().into_mat()).l1_norm() < 1e-9);
+        assert!((L.solve(&I) - L.inverse().into_mat()).l1_norm() < 1e-9);
+        assert!((&U.t() * &U.solve_transpose(&B) - &B).l1_norm() < 1e-9);
+        assert!((&L.t() * &L.solve_transpose(&B) - &B).l1_norm() < 1e-9);
+    }
+}
+
+#[test]
+fn triangular_product_test() {
+    const N: usize = 6;
+    for _ in 0..SMALL_REPS {
+        let (U1, U2) = (rand_upper::<N>(), rand_upper::<N>());
+        let (L1, L2) = (U1.t(), U2.t());
+        let B = Mat::<N, 3>::rand();
+        assert_eq_mat!(&U1 * &B, U1.as_mat() * &B);
+        assert_eq_mat!(&L1 * &B, L1.as_mat() * &B);
+        assert_eq_mat!((&U1 * &U2).into_mat(), U1.as_mat() * U2.as_mat());
+        assert_eq_mat!((&L1 * &L2).into_mat(), L1.as_mat() * L2.as_mat());
+    }
+}
@@ -0,0 +1,894 @@
+// Iterative solvers for Ax = b.
+//
+// Every solver starts from a guess x0 and stops once the relative
+// residual ‖b - Ax‖/‖b‖ is within the tolerance of its `IterOptions`,
+// returning the solution along with the residual at every step.
+
+use crate::prelude::*;
+
+use super::operator::LinearOperator;
+use super::preconditioner::Preconditioner;
+
+/// Stopping criteria for the iterative solvers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IterOptions {
+    tol: R,
+    max_iter: usize,
+}
+
+impl Default for IterOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IterOptions {
+    /// Relative residual of 1e-10, within 10000 iterations.
+    pub fn new() -> Self {
+        Self { tol: 1e-10, max_iter: 10000 }
+    }
+
+    /// Stop once ‖b - Ax‖/‖b‖ <= `tol`.
+    pub fn tol(mut self, tol: R) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Give up with `Error::TooManyIterations` after `n` iterations.
+    pub fn max_iter(mut self, n: usize) -> Self {
+        self.max_iter = n;
+        self
+    }
+}
+
+/// A converged solution.
+#[derive(Debug, Clone)]
+pub struct IterResult<const N: usize> {
+    pub x: Mat<N, 1>,
+    pub iterations: usize,
+    /// Relative residual before the first iteration and after each one,
+    /// so it has `iterations + 1` entries.
+    pub residuals: Vec<R>,
+}
+
+/// Relative residual ‖b - Ax‖/‖b‖, or ‖Ax‖ when b = 0.
+fn relative_residual<const N: usize>(
+    A: &Mat<N, N>,
+    b: &Mat<N, 1>,
+    x: &Mat<N, 1>,
+) -> R {
+    let r = (b - A * x).l2_norm();
+    match b.l2_norm() {
+        0. => r,
+        norm => r / norm,
+    }
+}
+
+/// Run `step` (which updates x in place) until the relative residual
+/// is within the tolerance.
+fn iterate<const N: usize>(
+    A: &Mat<N, N>,
+    b: &Mat<N, 1>,
+    x0: &Mat<N, 1>,
+    options: &IterOptions,
+    mut step: impl FnMut(&mut Mat<N, 1>),
+) -> Result<IterResult<N>> {
+    let mut x = x0.clone();
+    let mut residuals = vec![relative_residual(A, b, &x)];
+    for iterations in 0..=options.max_iter {
+        if residuals[iterations] <= options.tol {
+            return Ok(IterResult { x, iterations, residuals });
+        }
+        if iterations < options.max_iter {
+            step(&mut x);
+            residuals.push(relative_residual(A, b, &x));
+        }
+    }
+    Err(Error::TooManyIterations(options.max_iter))
+}
+
+/// The classical splitting methods A = M - N, each iterating
+/// x ← x + M⁻¹(b - Ax).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stationary {
+    /// M = D.
+    Jacobi,
+    /// M = D + L.
+    GaussSeidel,
+    /// Successive over-relaxation: M = D/ω + L.
+    Sor(R),
+    /// Symmetric SOR: a forward SOR sweep followed by a backward one.
+    Ssor(R),
+}
+
+/// One SOR sweep over the rows in `rows`, updating x in place.
+fn sor_sweep<const N: usize>(
+    A: &Mat<N, N>,
+    b: &Mat<N, 1>,
+    x: &mut Mat<N, 1>,
+    omega: R,
+    rows: impl Iterator<Item = usize>,
+) {
+    for i in rows {
+        let sigma: R =
+            (1..=N).filter(|&j| j != i).map(|j| A[(i, j)] * x[j]).sum();
+        x[i] += omega * ((b[i] - sigma) / A[(i, i)] - x[i]);
+    }
+}
+
+impl Stationary {
+    /// One iteration, updating x in place.
+    fn step<const N: usize>(
+        self,
+        A: &Mat<N, N>,
+        b: &Mat<N, 1>,
+        x: &mut Mat<N, 1>,
+    ) {
+        match self {
+            Stationary::Jacobi => {
+                let old = x.clone();
+                for i in 1..=N {
+                    let sigma: R = (1..=N)
+                        .filter(|&j| j != i)
+                        .map(|j| A[(i, j)] * old[j])
+                        .sum();
+                    x[i] = (b[i] - sigma) / A[(i, i)];
+                }
+            }
+            Stationary::GaussSeidel => sor_sweep(A, b, x, 1., 1..=N),
+            Stationary::Sor(omega) => sor_sweep(A, b, x, omega, 1..=N),
+            Stationary::Ssor(omega) => {
+                sor_sweep(A, b, x, omega, 1..=N);
+                sor_sweep(A, b, x, omega, (1..=N).rev());
+            }
+        }
+    }
+}
+
+/// Solve Ax = b by a stationary iterative method, starting from `x0`.
+///
+/// A MUST have a nonzero diagonal. The iteration converges for any x0
+/// if and only if the spectral radius of its iteration matrix is below
+/// 1 (see `spectral_radius`).
+pub fn stationary<const N: usize>(
+    A: &Mat<N, N>,
+    b: &Mat<N, 1>,
+    x0: &Mat<N, 1>,
+    method: Stationary,
+    options: &IterOptions,
+) -> Result<IterResult<N>> {
+    iterate(A, b, x0, options, |x| method.step(A, b, x))
+}
+
+/// Solve Ax = b by Jacobi iteration. Converges if A is strictly
+/// diagonally dominant.
+pub fn jacobi<const N: usize>(
+    A: &Mat<N, N>,
+    b: &Mat<N, 1>,
+    x0: &Mat<N, 1>,
+    options: &IterOptions,
+) -> Result<IterResult<N>> {
+    stationary(A, b, x0, Stationary::Jacobi, options)
+}
+
+/// Solve Ax = b by Gauss-Seidel iteration. Converges if A is strictly
+/// diagonally dominant, or symmetric positive definite.
+pub fn gauss_seidel<const N: usize>(
+    A: &Mat<N, N>,
+    b: &Mat<N, 1>,
+    x0: &Mat<N, 1>,
+    options: &IterOptions,
+) -> Result<IterResult<N>> {
+    stationary(A, b, x0, Stationary::GaussSeidel, options)
+}
+
+/// Solve Ax = b by successive over-relaxation. For A symmetric positive
+/// definite, converges if and only if 0 < ω < 2.
+pub fn sor<const N: usize>(
+    A: &Mat<N, N>,
+    b: &Mat<N, 1>,
+    x0: &Mat<N, 1>,
+    omega: R,
+    options: &IterOptions,
+) -> Result<IterResult<N>> {
+    stationary(A, b, x0, Stationary::Sor(omega), options)
+}
+
+/// Solve Ax = b by symmetric successive over-relaxation.
+pub fn ssor<const N: usize>(
+    A: &Mat<N, N>,
+    b: &Mat<N, 1>,
+    x0: &Mat<N, 1>,
+    omega: R,
+    options: &IterOptions,
+) -> Result<IterResult<N>> {
+    stationary(A, b, x0, Stationary::Ssor(omega), options)
+}
+
+/// Estimate the spectral radius of the iteration matrix G = I - M⁻¹A of
+/// a method, by applying G (one step with b = 0) to a random vector.
+///
+/// The growth rate of ‖Gᵏx‖ is averaged over the second half of the
+/// steps, so the estimate also works when the dominant eigenvalues are
+/// a complex pair, or G is defective.
+pub fn spectral_radius<const N: usize>(A: &Mat<N, N>, method: Stationary) -> R {
+    const STEPS: usize = 1000;
+    let zero = Mat::zero();
+    let mut x = Mat::<N, 1>::rand();
+    let mut log_growth = 0.;
+    for k in 1..=STEPS {
+        let before = x.l2_norm();
+        method.step(A, &zero, &mut x);
+        let after = x.l2_norm();
+        if after == 0. {
+            return 0.; // G is nilpotent on x.
+        }
+        if k > STEPS / 2 {
+            log_growth += (after / before).ln();
+        }
+        x.l2_normalize();
+    }
+    (log_growth / (STEPS - STEPS / 2) as R).exp()
+}
+
+/// Estimate the optimal SOR relaxation factor, 2/(1 + √(1 - ρ²)) with
+/// ρ the spectral radius of the Jacobi iteration matrix (Young's
+/// formula).
+///
+/// Exact for consistently ordered matrices with a real Jacobi spectrum,
+/// such as tridiagonal SPD matrices and the 2D Laplacian. Jacobi MUST
+/// converge on A.
+pub fn optimal_omega<const N: usize>(A: &Mat<N, N>) -> R {
+    let rho = spectral_radius(A, Stationary::Jacobi);
+    assert!(rho < 1., "Jacobi diverges: spectral radius {rho}");
+    2. / (1. + (1. - rho * rho).sqrt())
+}
+
+/// Solve Ax = b by the preconditioned conjugate gradient method,
+/// starting from `x0`. `A` is any linear operator, such as a `Mat`,
+/// `Symmetric`, `Sparse` or a pair of closures.
+///
+/// A and the preconditioner M MUST be symmetric positive definite.
+/// Returns `Error::NotPositiveDefinite` if a search direction p with
+/// pᵀAp <= 0 (or a residual r with rᵀM⁻¹r <= 0) shows otherwise. The
+/// residuals are those of the recurrence, which drift from the true
+/// ‖b - Ax‖ by rounding errors.
+pub fn conjugate_gradient<const N: usize, A, P>(
+    A: &A,
+    b: &Mat<N, 1>,
+    x0: &Mat<N, 1>,
+    M: &P,
+    options: &IterOptions,
+) -> Result<IterResult<N>>
+where
+    A: LinearOperator<N, N> + ?Sized,
+    P: Preconditioner<N>,
+{
+    let b_norm = match b.l2_norm() {
+        0. => 1.,
+        norm => norm,
+    };
+    let mut x = x0.clone();
+    let mut r = b - A.apply(&x);
+    let mut residuals = vec![r.l2_norm() / b_norm];
+    let mut z = M.apply(&r);
+    let mut p = z.clone();
+    let mut rz = r.dot(&z);
+    for iterations in 0..=options.max_iter {
+        if residuals[iterations] <= options.tol {
+            return Ok(IterResult { x, iterations, residuals });
+        }
+        if iterations == options.max_iter {
+            break;
+        }
+        if rz <= 0. {
+            return Err(Error::NotPositiveDefinite);
+        }
+        let Ap = A.apply(&p);
+        let pAp = p.dot(&Ap);
+        if pAp <= 0. {
+            return Err(Error::NotPositiveDefinite);
+        }
+        let alpha = rz / pAp;
+        x = &x + alpha * &p;
+        r = &r - alpha * &Ap;
+        residuals.push(r.l2_norm() / b_norm);
+        z = M.apply(&r);
+        let rz_next = r.dot(&z);
+        p = &z + (rz_next / rz) * &p;
+        rz = rz_next;
+    }
+    Err(Error::TooManyIterations(options.max_iter))
+}
+
+/// Which side of A the preconditioner M is applied on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// Solve M⁻¹Ax = M⁻¹b. The residuals are those of the
+    /// preconditioned system, relative to ‖M⁻¹b‖.
+    Left,
+    /// Solve AM⁻¹y = b with x = M⁻¹y. The residuals are those of the
+    /// original system.
+    Right,
+}
+
+/// Rotation (c, s) taking (a, b) to (r, 0).
+fn givens(a: R, b: R) -> (R, R) {
+    match a.hypot(b) {
+        0. => (1., 0.),
+        r => (a / r, b / r),
+    }
+}
+
+/// Solve Ax = b by restarted GMRES, GMRES(m): minimize the residual
+/// over a Krylov space built by Arnoldi (with modified Gram-Schmidt),
+/// restarting from the current x after every `restart` iterations.
+/// `A` is any linear operator.
+///
+/// The least squares problem on the Hessenberg matrix is solved as it
+/// grows, with Givens rotations, so the residual is known at every
+/// iteration without forming x. The residuals never increase, but may
+/// stagnate if `restart` is too small.
+pub fn gmres<const N: usize, A, P>(
+    A: &A,
+    b: &Mat<N, 1>,
+    x0: &Mat<N, 1>,
+    restart: usize,
+    M: &P,
+    side: Side,
+    options: &IterOptions,
+) -> Result<IterResult<N>>
+where
+    A: LinearOperator<N, N> + ?Sized,
+    P: Preconditioner<N>,
+{
+    assert!(restart > 0, "GMRES needs a restart length of at least 1");
+    // The operator whose Krylov space is searched.
+    let op = |v: &Mat<N, 1>| match side {
+        Side::Left => M.apply(&A.apply(v)),
+        Side::Right => A.apply(&M.apply(v)),
+    };
+    let b_norm = match side {
+        Side::Left => M.apply(b).l2_norm(),
+        Side::Right => b.l2_norm(),
+    };
+    let b_norm = if b_norm == 0. { 1. } else { b_norm };
+
+    let mut x = x0.clone();
+    let mut residuals = vec![];
+    let mut iterations = 0;
+    loop {
+        let r = match side {
+            Side::Left => M.apply(&(b - A.apply(&x))),
+            Side::Right => b - A.apply(&x),
+        };
+        let beta = r.l2_norm();
+        // On a restart, the residual is recomputed from x.
+        residuals.truncate(iterations);
+        residuals.push(beta / b_norm);
+        if beta / b_norm <= options.tol {
+            return Ok(IterResult { x, iterations, residuals });
+        }
+        if iterations == options.max_iter {
+            return Err(Error::TooManyIterations(options.max_iter));
+        }
+
+        let m = restart.min(options.max_iter - iterations);
+        let mut V = vec![(1. / beta) * &r];
+        // Columns of the Hessenberg matrix, reduced to upper
+        // triangular by the rotations.
+        let mut H: Vec<Vec<R>> = Vec::with_capacity(m);
+        let mut rotations: Vec<(R, R)> = Vec::with_capacity(m);
+        // Right-hand side of the least squares problem, ‖g‖ = β.
+        let mut g = vec![beta];
+        for j in 0..m {
+            let mut w = op(&V[j]);
+            let mut h = Vec::with_capacity(j + 2);
+            for v in &V {
+                let hij = w.dot(v);
+                w = &w - hij * v;
+                h.push(hij);
+            }
+            let h_next = w.l2_norm();
+            h.push(h_next);
+            for (i, &(c, s)) in rotations.iter().enumerate() {
+                (h[i], h[i + 1]) =
+                    (c * h[i] + s * h[i + 1], c * h[i + 1] - s * h[i]);
+            }
+            let (c, s) = givens(h[j], h[j + 1]);
+            (h[j], h[j + 1]) = (c * h[j] + s * h[j + 1], 0.);
+            g.push(-s * g[j]);
+            g[j] *= c;
+            rotations.push((c, s));
+            H.push(h);
+            iterations += 1;
+            residuals.push(g[j + 1].abs() / b_norm);
+            // A zero h_next means the Krylov space is invariant, and
+            // holds the exact solution.
+            if g[j + 1].abs() / b_norm <= options.tol || h_next == 0. {
+                break;
+            }
+            V.push((1. / h_next) * &w);
+        }
+
+        // Back substitution for y, then x += V y (or M⁻¹V y).
+        let k = H.len();
+        let mut y = vec![0.; k];
+        for i in (0..k).rev() {
+            let s: R = (i + 1..k).map(|j| H[j][i] * y[j]).sum();
+            y[i] = (g[i] - s) / H[i][i];
+        }
+        let mut dx = Mat::<N, 1>::zero();
+        for (v, y) in V.iter().zip(y) {
+            dx = &dx + y * v;
+        }
+        x = match side {
+            Side::Left => &x + &dx,
+            Side::Right => &x + &M.apply(&dx),
+        };
+    }
+}
+
+/// Solve Ax = b by the stabilized biconjugate gradient method
+/// (BiCGSTAB), right-preconditioned by M. `A` is any linear operator.
+///
+/// Like GMRES it handles nonsymmetric A, but with constant memory, at
+/// the cost of an irregular residual history. Each iteration takes two
+/// products with A. Returns `Error::Breakdown` if the method divides by
+/// zero before converging.
+pub fn bicgstab<const N: usize, A, P>(
+    A: &A,
+    b: &Mat<N, 1>,
+    x0: &Mat<N, 1>,
+    M: &P,
+    options: &IterOptions,
+) -> Result<IterResult<N>>
+where
+    A: LinearOperator<N, N> + ?Sized,
+    P: Preconditioner<N>,
+{
+    let b_norm = match b.l2_norm() {
+        0. => 1.,
+        norm => norm,
+    };
+    let mut x = x0.clone();
+    let mut r = b - A.apply(&x);
+    let mut residuals = vec![r.l2_norm() / b_norm];
+    // The shadow residual.
+    let r0 = r.clone();
+    let (mut rho, mut alpha, mut omega) = (1., 1., 1.);
+    let mut p = Mat::<N, 1>::zero();
+    let mut v = Mat::<N, 1>::zero();
+    for iterations in 0..=options.max_iter {
+        if residuals[iterations] <= options.tol {
+            return Ok(IterResult { x, iterations, residuals });
+        }
+        if iterations == options.max_iter {
+            break;
+        }
+        let rho_next = r0.dot(&r);
+        if rho_next == 0. || omega == 0. {
+            return Err(Error::Breakdown);
+        }
+        let beta = (rho_next / rho) * (alpha / omega);
+        rho = rho_next;
+        p = &r + beta * &(&p - omega * &v);
+        let p_hat = M.apply(&p);
+        v = A.apply(&p_hat);
+        let r0_v = r0.dot(&v);
+        if r0_v == 0. {
+            return Err(Error::Breakdown);
+        }
+        alpha = rho / r0_v;
+        let s = &r - alpha * &v;
+        if s.l2_norm() / b_norm <= options.tol {
+            x = &x + alpha * &p_hat;
+            residuals.push(s.l2_norm() / b_norm);
+            continue;
+        }
+        let s_hat = M.apply(&s);
+        let t = A.apply(&s_hat);
+        let t_t = t.dot(&t);
+        if t_t == 0. {
+            return Err(Error::Breakdown);
+        }
+        omega = t.dot(&s) / t_t;
+        x = &x + &(alpha * &p_hat + omega * &s_hat);
+        r = &s - omega * &t;
+        residuals.push(r.l2_norm() / b_norm);
+    }
+    Err(Error::TooManyIterations(options.max_iter))
+}
+
+/// Solve Ax = b by the minimal residual method (MINRES), for symmetric
+/// A that may be indefinite, where CG can break down. `A` is any
+/// linear operator.
+///
+/// The preconditioner M MUST be symmetric positive definite, else
+/// `Error::NotPositiveDefinite`; the residuals are then measured in the
+/// M⁻¹-norm, ‖r‖ₘ₋₁ = √(rᵀM⁻¹r). They never increase.
+pub fn minres<const N: usize, A, P>(
+    A: &A,
+    b: &Mat<N, 1>,
+    x0: &Mat<N, 1>,
+    M: &P,
+    options: &IterOptions,
+) -> Result<IterResult<N>>
+where
+    A: LinearOperator<N, N> + ?Sized,
+    P: Preconditioner<N>,
+{
+    // √(rᵀM⁻¹r), given z = M⁻¹r.
+    let m_norm = |r: &Mat<N, 1>, z: &Mat<N, 1>| match r.dot(z) {
+        rz if rz < 0. => Err(Error::NotPositiveDefinite),
+        rz => Ok(rz.sqrt()),
+    };
+    let b_norm = match m_norm(b, &M.apply(b))? {
+        0. => 1.,
+        norm => norm,
+    };
+    let mut x = x0.clone();
+    // Lanczos vectors: r2 is the newest, scaled by β, and y = M⁻¹r2.
+    let mut r1 = b - A.apply(&x);
+    let mut y = M.apply(&r1);
+    let mut r2 = r1.clone();
+    let mut beta = m_norm(&r1, &y)?;
+    let mut old_beta = 0.;
+    // The QR factorization of the Lanczos tridiagonal, by rotations.
+    let (mut cs, mut sn): (R, R) = (-1., 0.);
+    let (mut d_bar, mut epsilon) = (0., 0.);
+    let mut phi_bar = beta;
+    // The last three search directions.
+    let mut w = Mat::<N, 1>::zero();
+    let mut w2 = Mat::<N, 1>::zero();
+    let mut residuals = vec![phi_bar / b_norm];
+    for iterations in 0..=options.max_iter {
+        if residuals[iterations] <= options.tol {
+            return Ok(IterResult { x, iterations, residuals });
+        }
+        if iterations == options.max_iter {
+            break;
+        }
+        // Lanczos step.
+        let v = (1. / beta) * &y;
+        y = A.apply(&v);
+        if iterations > 0 {
+            y = &y - (beta / old_beta) * &r1;
+        }
+        let alpha = v.dot(&y);
+        y = &y - (alpha / beta) * &r2;
+        r1 = r2;
+        r2 = y;
+        y = M.apply(&r2);
+        old_beta = beta;
+        beta = m_norm(&r2, &y)?;
+
+        // Apply the previous rotation, then find the next one.
+        let old_epsilon = epsilon;
+        let delta = cs * d_bar + sn * alpha;
+        let g_bar = sn * d_bar - cs * alpha;
+        epsilon = sn * beta;
+        d_bar = -cs * beta;
+        let gamma = g_bar.hypot(beta).max(R::EPSILON);
+        (cs, sn) = (g_bar / gamma, beta / gamma);
+        let phi = cs * phi_bar;
+        phi_bar *= sn;
+
+        let w1 = w2;
+        w2 = w;
+        w = (1. / gamma) * &(&(&v - old_epsilon * &w1) - delta * &w2);
+        x = &x + phi * &w;
+        residuals.push(phi_bar / b_norm);
+    }
+    Err(Error::TooManyIterations(options.max_iter))
+}
+
+#[test]
+fn stationary_test() {
+    const N: usize = 6;
+    let options = IterOptions::new().tol(1e-12);
+    for _ in 0..SMALL_REPS {
+        // Strictly diagonally dominant, so every method converges.
+        let mut A = Mat::<N, N>::rand();
+        A.add_identity(N as R);
+        let b = Mat::<N, 1>::rand();
+        let x0 = Mat::zero();
+        for method in [
+            Stationary::Jacobi,
+            Stationary::GaussSeidel,
+            Stationary::Sor(1.1),
+            Stationary::Ssor(0.9),
+        ] {
+            let result = stationary(&A, &b, &x0, method, &options).unwrap();
+            assert_eq!(result.residuals.len(), result.iterations + 1);
+            assert!(*result.residuals.last().unwrap() <= 1e-12);
+            assert!(relative_residual(&A, &b, &result.x) < 1e-11);
+        }
+    }
+}
+
+#[test]
+fn stationary_divergence_test() {
+    let A = mat![1, 2; 2, 1];
+    let b = mat![1; 1];
+    let options = IterOptions::new().max_iter(50);
+    let result = jacobi(&A, &b, &Mat::zero(), &options);
+    assert!(matches!(result, Err(Error::TooManyIterations(50))));
+    assert!((spectral_radius(&A, Stationary::Jacobi) - 2.).abs() < 1e-9);
+    // Already converged: no iterations.
+    let x = mat![1. / 3.; 1. / 3.];
+    let result = jacobi(&A, &b, &x, &options).unwrap();
+    assert_eq!((result.iterations, result.residuals.len()), (0, 1));
+}
+
+#[test]
+fn spectral_radius_test() {
+    // For the 1D Laplacian, ρ(Jacobi) = cos(πh), ρ(Gauss-Seidel) is its
+    // square, and SOR with the optimal ω has ρ = ω - 1.
+    const N: usize = 20;
+    let A = crate::gallery::laplacian_1d::<N>();
+    let c = (std::f64::consts::PI / (N + 1) as R).cos();
+    let jacobi = spectral_radius(&A, Stationary::Jacobi);
+    assert!((jacobi - c).abs() < 1e-6, "{jacobi}");
+    let gauss_seidel = spectral_radius(&A, Stationary::GaussSeidel);
+    assert!((gauss_seidel - c * c).abs() < 1e-6, "{gauss_seidel}");
+    let omega = optimal_omega(&A);
+    let expected = 2. / (1. + (1. - c * c).sqrt());
+    assert!((omega - expected).abs() < 1e-6, "{omega}");
+    let sor = spectral_radius(&A, Stationary::Sor(omega));
+    assert!((sor - (omega - 1.)).abs() < 1e-2, "{sor}");
+}
+
+#[test]
+fn sor_speedup_test() {
+    // Gauss-Seidel takes about half the iterations of Jacobi, and SOR
+    // with the optimal ω far fewer still.
+    const N: usize = 20;
+    let A = crate::gallery::laplacian_1d::<N>();
+    let b = Mat::<N, 1>::from_fn(|_, _| 1.);
+    let x0 = Mat::zero();
+    let options = IterOptions::new().tol(1e-8);
+    let jacobi = jacobi(&A, &b, &x0, &options).unwrap().iterations;
+    let gauss_seidel = gauss_seidel(&A, &b, &x0, &options).unwrap().iterations;
+    let sor = sor(&A, &b, &x0, optimal_omega(&A), &options).unwrap();
+    let ssor = ssor(&A, &b, &x0, 1.5, &options).unwrap();
+    assert!(gauss_seidel * 2 <= jacobi + 10, "{gauss_seidel} vs {jacobi}");
+    assert!(
+        sor.iterations * 5 < gauss_seidel,
+        "{} vs {gauss_seidel}",
+        sor.iterations
+    );
+    assert!(ssor.iterations < gauss_seidel);
+    assert_eq_mat!(&A * &sor.x, b, 1e-7);
+}
+
+#[test]
+fn conjugate_gradient_test() {
+    use super::preconditioner::*;
+
+    const N: usize = 8;
+    let options = IterOptions::new().tol(1e-12);
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::symmetric_positive_definite();
+        let b = Mat::<N, 1>::rand();
+        let x0 = Mat::zero();
+        let cg = conjugate_gradient(&A, &b, &x0, &Identity, &options).unwrap();
+        assert!(cg.iterations <= N + 2, "{}", cg.iterations);
+        assert!(relative_residual(&A, &b, &cg.x) < 1e-10);
+        let pcg = conjugate_gradient(&A, &b, &x0, &Jacobi::new(&A), &options);
+        assert!(relative_residual(&A, &b, &pcg.unwrap().x) < 1e-10);
+        // The exact Cholesky factor solves in one step.
+        let ic = IncompleteCholesky::from_mat(&A).unwrap();
+        let pcg = conjugate_gradient(&A, &b, &x0, &ic, &options).unwrap();
+        assert!(pcg.iterations <= 2);
+        assert_eq!(pcg.residuals.len(), pcg.iterations + 1);
+    }
+}
+
+#[test]
+fn conjugate_gradient_laplacian_test() {
+    use super::preconditioner::*;
+
+    // The 2D Laplacian, matrix-free through its sparse form.
+    const K: usize = 10;
+    const N: usize = K * K;
+    let A = crate::gallery::laplacian_2d_sparse::<K, N>();
+    let b = Mat::<N, 1>::rand();
+    let x0 = Mat::zero();
+    let options = IterOptions::new().tol(1e-10);
+    let plain = conjugate_gradient(&A, &b, &x0, &Identity, &options).unwrap();
+    let ic = IncompleteCholesky::new(&A).unwrap();
+    let pcg = conjugate_gradient(&A, &b, &x0, &ic, &options).unwrap();
+    assert!(pcg.iterations * 2 < plain.iterations);
+    assert!((&b - &A * &pcg.x).l2_norm() < 1e-9 * b.l2_norm());
+    assert!((&plain.x - &pcg.x).l2_norm() < 1e-8 * pcg.x.l2_norm());
+
+    // The same operator as a five-point stencil, never stored.
+    let stencil = |u: &Mat<N, 1>| {
+        let at = |x: usize, y: usize| match (x, y) {
+            (1..=K, 1..=K) => u[(y - 1) * K + x],
+            _ => 0.,
+        };
+        Mat::from_fn(|i, _| {
+            let (x, y) = ((i - 1) % K + 1, (i - 1) / K + 1);
+            4. * at(x, y)
+                - at(x - 1, y)
+                - at(x + 1, y)
+                - at(x, y - 1)
+                - at(x, y + 1)
+        })
+    };
+    // Symmetric, so it is its own transpose.
+    let stencil = (stencil, stencil);
+    let free = conjugate_gradient(&stencil, &b, &x0, &Identity, &options);
+    let free = free.unwrap();
+    assert!(free.iterations.abs_diff(plain.iterations) <= 1);
+    assert!((&plain.x - &free.x).l2_norm() < 1e-8 * plain.x.l2_norm());
+}
+
+#[test]
+fn conjugate_gradient_indefinite_test() {
+    use super::preconditioner::Identity;
+
+    let A = Diagonal::from(mat![1; -1]);
+    let result = conjugate_gradient(
+        &A,
+        &mat![1; 1],
+        &Mat::zero(),
+        &Identity,
+        &IterOptions::new(),
+    );
+    assert!(matches!(result, Err(Error::NotPositiveDefinite)));
+}
+
+#[test]
+fn gmres_test() {
+    use super::preconditioner::*;
+
+    const N: usize = 8;
+    let options = IterOptions::new().tol(1e-12);
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::positive_definite();
+        let b = Mat::<N, 1>::rand();
+        let x0 = Mat::zero();
+        // Unrestarted, GMRES is exact after N iterations.
+        let full = gmres(&A, &b, &x0, N, &Identity, Side::Right, &options);
+        let full = full.unwrap();
+        assert!(full.iterations <= N);
+        assert!(relative_residual(&A, &b, &full.x) < 1e-10);
+        assert_eq!(full.residuals.len(), full.iterations + 1);
+        let jacobi = Jacobi::new(&A);
+        for side in [Side::Left, Side::Right] {
+            let result = gmres(&A, &b, &x0, 3, &jacobi, side, &options);
+            assert!(relative_residual(&A, &b, &result.unwrap().x) < 1e-10);
+        }
+    }
+}
+
+#[test]
+fn gmres_convection_diffusion_test() {
+    use super::preconditioner::*;
+
+    // Upwinded convection-diffusion, -u'' + 10u' = 1: nonsymmetric,
+    // and applied matrix-free through its sparse form.
+    const N: usize = 50;
+    let h = 1. / (N + 1) as R;
+    let mut entries = vec![];
+    for i in 1..=N {
+        entries.push((i, i, 2. + 10. * h));
+        if i > 1 {
+            entries.push((i, i - 1, -1. - 10. * h));
+        }
+        if i < N {
+            entries.push((i, i + 1, -1.));
+        }
+    }
+    let A = Sparse::<N, N>::from_triplets(&entries);
+    let b = Mat::<N, 1>::from_fn(|_, _| h * h);
+    let options = IterOptions::new().tol(1e-10);
+    let result =
+        gmres(&A, &b, &Mat::zero(), 20, &Identity, Side::Right, &options);
+    let result = result.unwrap();
+    // Minimal residual: the residuals never increase.
+    for pair in result.residuals.windows(2) {
+        assert!(pair[1] <= pair[0] * (1. + 1e-9), "{pair:?}");
+    }
+    let true_residual = (&b - &A * &result.x).l2_norm() / b.l2_norm();
+    assert!(true_residual < 1e-9, "{true_residual}");
+
+    // ILU(0) of a tridiagonal matrix is exact.
+    let ilu = IncompleteLu::new(&A).unwrap();
+    let result = gmres(&A, &b, &Mat::zero(), 20, &ilu, Side::Right, &options);
+    assert!(result.unwrap().iterations <= 2);
+
+    let options = options.max_iter(5);
+    let result =
+        gmres(&A, &b, &Mat::zero(), 2, &Identity, Side::Left, &options);
+    assert!(matches!(result, Err(Error::TooManyIterations(5))));
+}
+
+#[test]
+fn bicgstab_test() {
+    use super::preconditioner::*;
+
+    const N: usize = 8;
+    let options = IterOptions::new().tol(1e-12);
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::positive_definite();
+        let b = Mat::<N, 1>::rand();
+        let x0 = Mat::zero();
+        for result in [
+            bicgstab(&A, &b, &x0, &Identity, &options),
+            bicgstab(&A, &b, &x0, &Jacobi::new(&A), &options),
+        ] {
+            let result = result.unwrap();
+            assert_eq!(result.residuals.len(), result.iterations + 1);
+            assert!(relative_residual(&A, &b, &result.x) < 1e-10);
+        }
+    }
+}
+
+#[test]
+fn bicgstab_breakdown_test() {
+    use super::preconditioner::*;
+
+    let options = IterOptions::new();
+    // r0 = b and v = Ab are orthogonal.
+    let A = mat![0, 1; 1, 0];
+    let result = bicgstab(&A, &mat![1; 0], &Mat::zero(), &Identity, &options);
+    assert!(matches!(result, Err(Error::Breakdown)));
+    // s = [-1; 1] is in the null space of A, so t = As = 0.
+    let A = mat![-1, -1; 0, 0];
+    let result = bicgstab(&A, &mat![1; 1], &Mat::zero(), &Identity, &options);
+    assert!(matches!(result, Err(Error::Breakdown)));
+}
+
+#[test]
+fn minres_test() {
+    use super::preconditioner::*;
+
+    const N: usize = 8;
+    let options = IterOptions::new().tol(1e-12);
+    for _ in 0..SMALL_REPS {
+        // Symmetric, with eigenvalues ±0.5, ±1.5, ±2.5 and ±3.5.
+        let lambda = Mat::<N, 1>::from_fn(|i, _| i as R);
+        let mut A = Mat::spd_with_eigenvalues(&lambda);
+        A.add_identity(-4.5);
+        let b = Mat::<N, 1>::rand();
+        let result = minres(&A, &b, &Mat::zero(), &Identity, &options);
+        let result = result.unwrap();
+        for pair in result.residuals.windows(2) {
+            assert!(pair[1] <= pair[0] * (1. + 1e-9), "{pair:?}");
+        }
+        assert!(relative_residual(&A, &b, &result.x) < 1e-9);
+    }
+}
+
+#[test]
+fn minres_preconditioned_test() {
+    use super::preconditioner::*;
+
+    // A shifted Laplacian: indefinite, but with a positive diagonal, so
+    // Jacobi is an SPD preconditioner.
+    const N: usize = 40;
+    let mut A = crate::gallery::laplacian_1d::<N>();
+    A.add_identity(-1.5);
+    let b = Mat::<N, 1>::rand();
+    let options = IterOptions::new().tol(1e-10);
+    let jacobi = Jacobi::new(&A);
+    let result = minres(&A, &b, &Mat::zero(), &jacobi, &options).unwrap();
+    assert!(relative_residual(&A, &b, &result.x) < 1e-8);
+    let cg = conjugate_gradient(&A, &b, &Mat::zero(), &Identity, &options);
+    assert!(matches!(cg, Err(Error::NotPositiveDefinite)));
+
+    // The Jacobi preconditioner of an indefinite diagonal is not SPD.
+    let D = Diagonal::from(mat![1; -1]);
+    let result = minres(
+        &D,
+        &mat![1; 2],
+        &Mat::zero(),
+        &Jacobi::new(&D.to_mat()),
+        &options,
+    );
+    assert!(matches!(result, Err(Error::NotPositiveDefinite)));
+}
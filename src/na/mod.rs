@@ -1,9 +1,15 @@
 // Numerical Analysis functions
 
+pub mod iterative;
+pub mod operator;
+pub mod preconditioner;
 pub mod qr_decomp;
+pub mod refinement;
 
 use crate::prelude::*;
 
+use operator::LinearOperator;
+
 /// Clones the matrix, and writes the Cholesky factor into the
 /// lower-triangular half of the matrix.
 ///
@@ -12,7 +18,7 @@ pub fn cholesky<const N: usize>(A: &mut Mat<N, N>) {
     for k in 1..N {
         A[(k, k)] = A[(k, k)].sqrt();
         for j in k + 1..=N {
-            A[(j, k)] = A[(j, k)] / A[(k, k)];
+            A[(j, k)] /= A[(k, k)];
         }
         for j in k + 1..=N {
             for i in j..=N {
@@ -40,22 +46,59 @@ fn cholesky_test() {
     }
 }
 
+/// Cholesky factorization on packed storage. Overwrites the lower
+/// triangle (which is all that is stored) with the Cholesky factor.
+///
+/// Input matrix MUST be positive definite.
+pub fn cholesky_packed<const N: usize>(A: &mut Symmetric<N>) {
+    for k in 1..=N {
+        A[(k, k)] = A[(k, k)].sqrt();
+        for j in k + 1..=N {
+            A[(j, k)] /= A[(k, k)];
+        }
+        for j in k + 1..=N {
+            for i in j..=N {
+                A[(i, j)] -= A[(i, k)] * A[(j, k)];
+            }
+        }
+    }
+}
+
+/// LDLᵀ factorization on packed storage, without pivoting. Overwrites
+/// the diagonal with D, and the strictly lower triangle with the
+/// multipliers of the unit lower-triangular L.
+///
+/// Every leading principal minor of the input MUST be non-singular.
+pub fn ldlt<const N: usize>(A: &mut Symmetric<N>) {
+    for j in 1..=N {
+        for k in 1..j {
+            A[(j, j)] -= A[(j, k)] * A[(j, k)] * A[(k, k)];
+        }
+        for i in j + 1..=N {
+            for k in 1..j {
+                A[(i, j)] -= A[(i, k)] * A[(j, k)] * A[(k, k)];
+            }
+            A[(i, j)] /= A[(j, j)];
+        }
+    }
+}
+
 /// Evaluate a polynomial in linear time, using Horner's Method.
 /// `p` is read highest-degree first.
-pub fn horners(p: &Vec<R>, x: R) -> R {
+pub fn horners(p: &[R], x: R) -> R {
     if p.is_empty() {
         return 0.;
     }
     let mut v = p[0];
-    for k in 1..p.len() {
-        v = p[k] + (x * v)
+    for pk in &p[1..] {
+        v = pk + (x * v)
     }
     v
 }
 
 #[test]
 fn horners_test() {
-    fn polyval(p: &Vec<R>, x: R) -> R {
+    fn polyval(p: &[R], x: R) -> R {
         p.iter()
             .rev()
             .enumerate()
@@ -75,40 +118,321 @@ fn horners_test() {
     test!([1.2], 91.1);
 }
 
-pub fn backward_sub<const N: usize>(A: &Mat<N, N>, b: &Mat<N, 1>) -> Mat<N, 1> {
-    assert!(A.is_upper_triangular(), "A needs to be upper-triangular:\n{A:?}");
-    let mut x = b.clone();
-    for k in (1..=N).rev() {
-        let mut s = b[k];
+/// Clones the matrix, and writes the LU factors into it: the
+/// multipliers of the unit lower-triangular L strictly below the
+/// diagonal, and U on and above the diagonal.
+///
+/// No pivoting is done, so every leading principal minor of the input
+/// MUST be non-singular.
+pub fn lu<const N: usize>(A: &mut Mat<N, N>) {
+    for k in 1..N {
+        for i in k + 1..=N {
+            A[(i, k)] /= A[(k, k)];
+        }
+        for j in k + 1..=N {
+            for i in k + 1..=N {
+                A[(i, j)] -= A[(i, k)] * A[(k, j)];
+            }
+        }
+    }
+}
+
+#[test]
+fn lu_test() {
+    const N: usize = 6;
+    for _ in 0..REPS {
+        let mut A = Mat::<N, N>::rand();
+        A.add_identity(N as R); // diagonally dominant: no pivoting needed.
+        let (L, U) = A.lu();
+        assert_eq_mat!(&L * U.as_mat(), A);
+    }
+}
+
+/// Clones the matrix, and writes the LU factors of PA into it, in the
+/// same layout as `lu`. Rows are swapped so that each pivot is the
+/// largest (in magnitude) in its column. Returns P.
+pub fn lu_partial_pivot<const N: usize>(A: &mut Mat<N, N>) -> Permutation<N> {
+    let mut P = Permutation::eye();
+    for k in 1..N {
+        let p = (k..=N)
+            .max_by(|&a, &b| A[(a, k)].abs().total_cmp(&A[(b, k)].abs()))
+            .unwrap_or(k);
+        if p != k {
+            A.swap_rows(k, p);
+            P.swap(k, p);
+        }
+        if A[(k, k)] == 0. {
+            continue; // singular: this column is already eliminated.
+        }
+        for i in k + 1..=N {
+            A[(i, k)] /= A[(k, k)];
+        }
         for j in k + 1..=N {
-            s -= A[(k, j)] * x[j];
+            for i in k + 1..=N {
+                A[(i, j)] -= A[(i, k)] * A[(k, j)];
+            }
+        }
+    }
+    P
+}
+
+#[test]
+fn lu_partial_pivot_test() {
+    const N: usize = 6;
+    for _ in 0..REPS {
+        let A = Mat::<N, N>::rand();
+        let (P, L, U) = A.lu_pivoted();
+        assert_eq_mat!(&L * U.as_mat(), &P * &A, 1e-4);
+        // partial pivoting bounds the multipliers by 1.
+        assert!(L.as_mat().data.iter().flatten().all(|l| l.abs() <= 1.));
+    }
+}
+
+/// `lu_partial_pivot` for a banded matrix, with `lower` nonzero
+/// diagonals below the main diagonal and `upper` above it. Elimination
+/// stays within the band, which pivoting widens to `lower + upper`
+/// above the diagonal, so it takes O(N·lower·(lower + upper)) flops.
+pub fn lu_banded<const N: usize>(
+    A: &mut Mat<N, N>,
+    lower: usize,
+    upper: usize,
+) -> Permutation<N> {
+    let mut P = Permutation::eye();
+    for k in 1..N {
+        let last_row = (k + lower).min(N);
+        let last_col = (k + lower + upper).min(N);
+        let p = (k..=last_row)
+            .max_by(|&a, &b| A[(a, k)].abs().total_cmp(&A[(b, k)].abs()))
+            .unwrap_or(k);
+        if p != k {
+            A.swap_rows(k, p);
+            P.swap(k, p);
+        }
+        if A[(k, k)] == 0. {
+            continue;
+        }
+        for i in k + 1..=last_row {
+            A[(i, k)] /= A[(k, k)];
+        }
+        for j in k + 1..=last_col {
+            for i in k + 1..=last_row {
+                A[(i, j)] -= A[(i, k)] * A[(k, j)];
+            }
         }
-        x[k] = s / A[(k, k)];
     }
-    x
+    P
+}
+
+#[test]
+fn lu_banded_test() {
+    const N: usize = 10;
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::banded(2, 1);
+        let mut LU = A.clone();
+        let P = lu_banded(&mut LU, 2, 1);
+        let L = Mat::from_fn(|i, j| match i.cmp(&j) {
+            std::cmp::Ordering::Less => 0.,
+            std::cmp::Ordering::Equal => 1.,
+            std::cmp::Ordering::Greater => LU[(i, j)],
+        });
+        let U = LU.upper_triangular();
+        assert!((&L * &U - &P * &A).l1_norm() < 1e-12);
+        // U has at most lower + upper diagonals above the main one.
+        assert!((1..=N).all(|i| (i + 4..=N).all(|j| U[(i, j)] == 0.)));
+    }
+}
+
+/// Estimate ‖A⁻¹‖₁ from a few solves with A and Aᵀ, by Hager's method
+/// with Higham's safeguard (as in LAPACK's condition estimators).
+/// Usually within a factor of 3 of the truth, and never above it.
+pub fn inverse_l1_norm_estimate<const N: usize>(
+    solve: impl Fn(&Mat<N, 1>) -> Mat<N, 1>,
+    solve_transpose: impl Fn(&Mat<N, 1>) -> Mat<N, 1>,
+) -> R {
+    let mut x = Mat::<N, 1>::from_fn(|_, _| 1. / N as R);
+    let mut estimate = 0.;
+    for _ in 0..5 {
+        let y = solve(&x);
+        estimate = y.l1_norm();
+        let sign = Mat::from_fn(|i, _| if y[i] < 0. { -1. } else { 1. });
+        let z = solve_transpose(&sign);
+        let j = (1..=N).max_by(|&a, &b| z[a].abs().total_cmp(&z[b].abs()));
+        let j = j.unwrap_or(1);
+        if z[j].abs() <= z.dot(&x) {
+            break;
+        }
+        x.canonical_basis(j);
+    }
+    // Hager's method can be fooled; this alternating vector guards it.
+    let b = Mat::<N, 1>::from_fn(|i, _| {
+        let sign = if i % 2 == 1 { 1. } else { -1. };
+        sign * (1. + (i - 1) as R / (N.max(2) - 1) as R)
+    });
+    let alternative = 2. * solve(&b).l1_norm() / (3 * N) as R;
+    estimate.max(alternative)
+}
+
+#[test]
+fn inverse_l1_norm_estimate_test() {
+    const N: usize = 6;
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::rand();
+        let (P, L, U) = A.lu_pivoted();
+        let estimate = inverse_l1_norm_estimate(
+            |b| U.solve(&L.solve(&(&P * b))),
+            |b| &P.t() * &L.solve_transpose(&U.solve_transpose(b)),
+        );
+        let exact = Mat::<N, N>::from_fn(|i, j| {
+            let mut e = Mat::<N, 1>::zero();
+            e.canonical_basis(j);
+            U.solve(&L.solve(&(&P * &e)))[i]
+        })
+        .l1_norm();
+        assert!(estimate <= exact * (1. + 1e-9));
+        assert!(estimate >= exact / 10., "{estimate} vs {exact}");
+    }
+}
+
+/// Which half of the matrix `trsm` reads; the other is ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Triangle {
+    Upper,
+    Lower,
+}
+
+/// Whether `trsm` solves with the matrix, or with its transpose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transpose {
+    No,
+    Yes,
+}
+
+/// Whether `trsm` reads the diagonal, or takes it to be all ones (as
+/// for the L of an LU factorization).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Diag {
+    NonUnit,
+    Unit,
+}
+
+/// Solve op(T)X = B for X, with T triangular and op(T) either T or Tᵀ,
+/// like BLAS's trsm. Each column of B is one right-hand side.
+pub fn trsm<const N: usize, const K: usize>(
+    T: &Mat<N, N>,
+    B: &Mat<N, K>,
+    triangle: Triangle,
+    transpose: Transpose,
+    diag: Diag,
+) -> Mat<N, K> {
+    let op = |i, j| match transpose {
+        Transpose::No => T[(i, j)],
+        Transpose::Yes => T[(j, i)],
+    };
+    // op(T) is upper-triangular, so it's solved from the bottom up.
+    let upper = (triangle == Triangle::Upper) == (transpose == Transpose::No);
+    let mut X = B.clone();
+    for c in 1..=K {
+        let x = X.col_mut(c);
+        for step in 0..N {
+            let (k, known) = if upper {
+                (N - step, N - step + 1..=N)
+            } else {
+                (step + 1, 1..=step)
+            };
+            let mut s = x[k];
+            for j in known {
+                s -= op(k, j) * x[j];
+            }
+            x[k] = match diag {
+                Diag::NonUnit => s / op(k, k),
+                Diag::Unit => s,
+            };
+        }
+    }
+    X
+}
+
+#[test]
+fn trsm_test() {
+    const N: usize = 6;
+    for _ in 0..SMALL_REPS {
+        let T = Mat::<N, N>::rand();
+        let B = Mat::<N, 3>::rand();
+        for triangle in [Triangle::Upper, Triangle::Lower] {
+            for transpose in [Transpose::No, Transpose::Yes] {
+                for diag in [Diag::NonUnit, Diag::Unit] {
+                    let mut A = match triangle {
+                        Triangle::Upper => T.upper_triangular(),
+                        Triangle::Lower => T.lower_triangular(),
+                    };
+                    if diag == Diag::Unit {
+                        (1..=N).for_each(|i| A[(i, i)] = 1.);
+                    }
+                    if transpose == Transpose::Yes {
+                        A.transpose_inplace();
+                    }
+                    let X = trsm(&T, &B, triangle, transpose, diag);
+                    let scale = A.l1_norm() * X.l1_norm();
+                    assert!((&A * &X - &B).l1_norm() < 1e-12 * scale);
+                }
+            }
+        }
+    }
+}
+
+/// Solve UX = B, with U upper-triangular, via backward substitution.
+pub fn backward_sub<const N: usize, const K: usize>(
+    U: &UpperTriangular<N>,
+    B: &Mat<N, K>,
+) -> Mat<N, K> {
+    trsm(U.as_mat(), B, Triangle::Upper, Transpose::No, Diag::NonUnit)
 }
 
 #[test]
 fn backward_sub_test() {
     const N: usize = 6;
     for _ in 0..HIGH_REPS {
-        let A = Mat::<N, N>::rand().upper_triangular();
+        let A = UpperTriangular::from(Mat::<N, N>::rand());
         let b = Mat::<N, 1>::rand();
         let x = backward_sub(&A, &b);
-        assert_eq_mat!(A * x, b, 1e-4);
+        assert_eq_mat!(&A * &x, b, 1e-4);
     }
 }
 
-/// Determine the dominant eigenvector of a matrix, and its
+/// Solve LX = B, with L lower-triangular, via forward substitution.
+pub fn forward_sub<const N: usize, const K: usize>(
+    L: &LowerTriangular<N>,
+    B: &Mat<N, K>,
+) -> Mat<N, K> {
+    trsm(L.as_mat(), B, Triangle::Lower, Transpose::No, Diag::NonUnit)
+}
+
+#[test]
+fn forward_sub_test() {
+    const N: usize = 6;
+    for _ in 0..HIGH_REPS {
+        let A = LowerTriangular::from(Mat::<N, N>::rand());
+        let b = Mat::<N, 1>::rand();
+        let x = forward_sub(&A, &b);
+        assert_eq_mat!(&A * &x, b, 1e-4);
+    }
+}
+
+/// Determine the dominant eigenvector of a linear operator, and its
 /// corresponding eigenvalue.
-pub fn power_iteration<const N: usize>(A: &Mat<N, N>) -> (R, Mat<N, 1>) {
-    let mut v = A.col(1).clone();
+pub fn power_iteration<const N: usize, A>(A: &A) -> (R, Mat<N, 1>)
+where
+    A: LinearOperator<N, N> + ?Sized,
+{
+    let mut e1 = Mat::zero();
+    e1.canonical_basis(1);
+    let mut v = A.apply(&e1);
     loop {
-        let mut v2 = A * &v;
+        let mut v2 = A.apply(&v);
         v2.l2_normalize();
         if (&v2 - &v).l2_norm() < 1e-15 {
             v = v2;
-            break (v.dot(A * &v), v);
+            break (v.dot(A.apply(&v)), v);
         }
         v = v2;
     }
@@ -124,11 +448,39 @@ fn power_iteration_test() {
     }
 }
 
+#[test]
+fn power_iteration_operator_test() {
+    // Inverse iteration as power iteration on a shifted inverse, which
+    // is only ever applied through a factorization.
+    const N: usize = 6;
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::symmetric_positive_definite();
+        let L = A.cholesky();
+        let inverse = |x: &Mat<N, 1>| L.solve_transpose(&L.solve(x));
+        let (mu, v) = power_iteration(&(inverse, inverse));
+        assert_eq_mat!(&A * &v, mu.recip() * &v, 1e-4);
+        assert_eq_tol!(rayleigh_quotient(&v, &A), mu.recip(), 1e-6);
+    }
+}
+
+#[test]
+fn power_iteration_symmetric_test() {
+    const N: usize = 6;
+    for _ in 0..SMALL_REPS {
+        let A = Symmetric::<N>::rand();
+        let (lambda, v) = power_iteration(&A);
+        assert_eq_mat!(&A * &v, lambda * v, 1e-4);
+    }
+}
+
 /// Rayleigh Quotient.
 /// Useful for calculating the eigenvalue of `v` when it is known that
 /// it is an eigenvector of `A`.
-pub fn rayleigh_quotient<const N: usize>(v: &Mat<N, 1>, A: &Mat<N, N>) -> R {
-    v.dot(A * v) / v.dot(v) // = vᵀAv/vᵀv
+pub fn rayleigh_quotient<const N: usize, A>(v: &Mat<N, 1>, A: &A) -> R
+where
+    A: LinearOperator<N, N> + ?Sized,
+{
+    v.dot(A.apply(v)) / v.dot(v) // = vᵀAv/vᵀv
 }
 
 /// Inverse iteration.
@@ -141,9 +493,10 @@ pub fn inverse_iteration<const N: usize>(
     let mut v = A.col(1).clone();
     let mut B = A.clone();
     B.add_identity(-a);
+    let lu = Lu::new(&B);
 
     loop {
-        v = B.solve_lls(&v);
+        v = lu.solve(&v);
         v.l2_normalize();
 
         if v.is_eigenvector_of(&B, 1e-8) {
@@ -179,20 +532,22 @@ fn inverse_iteration_against_power_iteration() {
 pub fn rayleigh_quotient_iteration<const N: usize>(
     A: &Mat<N, N>,
 ) -> Result<(R, Mat<N, 1>)> {
+    // The shift changes at every step, so each one takes a new
+    // factorization.
     let B = |mu: R| {
         let mut B = A.clone();
         B.add_identity(-mu);
-        B
+        Lu::new(&B)
     };
 
     let mut x = Mat::<N, 1>::rand();
     let mut mu = x.dot(A * &x);
-    let mut y = B(mu).solve_lls(&x);
+    let mut y = B(mu).solve(&x);
     mu += y.dot(&x).recip();
 
     for _ in 0..100 {
         x.l2_normalize();
-        y = B(mu).solve_lls(&x);
+        y = B(mu).solve(&x);
         let lambda = y.dot(&x);
         mu += lambda.recip();
         if (&y - lambda * &x).l2_norm() / y.l2_norm() < 1e-9 {
@@ -219,3 +574,79 @@ fn rayleigh_quotient_iteration_test() {
         }
     }
 }
+
+/// All eigenvalues and eigenvectors of a symmetric matrix, via the
+/// cyclic Jacobi eigenvalue algorithm. Returns the eigenvalues in
+/// ascending order, and the matching orthonormal eigenvectors as the
+/// columns of V, so that AV = VΛ.
+pub fn symmetric_eigen<const N: usize>(
+    S: &Symmetric<N>,
+) -> Result<(Mat<N, 1>, Mat<N, N>)> {
+    const MAX_SWEEPS: usize = 100;
+    let mut A = S.to_mat();
+    let mut V = Mat::<N, N>::eye();
+    let scale: R = A.data.iter().flatten().map(|v| v * v).sum::<R>().sqrt();
+
+    let mut converged = false;
+    for _ in 0..MAX_SWEEPS {
+        let off: R = (1..=N)
+            .flat_map(|j| (1..j).map(move |i| (i, j)))
+            .map(|(i, j)| 2. * A[(i, j)] * A[(i, j)])
+            .sum();
+        if off.sqrt() <= 1e-15 * scale {
+            converged = true;
+            break;
+        }
+        for p in 1..N {
+            for q in p + 1..=N {
+                if A[(p, q)] == 0. {
+                    continue;
+                }
+                // Rotation by angle chosen so that A[(p, q)] becomes 0.
+                let theta = (A[(q, q)] - A[(p, p)]) / (2. * A[(p, q)]);
+                let t = theta.signum() / (theta.abs() + theta.hypot(1.));
+                let c = t.hypot(1.).recip();
+                let s = t * c;
+                for k in 1..=N {
+                    let (akp, akq) = (A[(k, p)], A[(k, q)]);
+                    A[(k, p)] = c * akp - s * akq;
+                    A[(k, q)] = s * akp + c * akq;
+                }
+                for k in 1..=N {
+                    let (apk, aqk) = (A[(p, k)], A[(q, k)]);
+                    A[(p, k)] = c * apk - s * aqk;
+                    A[(q, k)] = s * apk + c * aqk;
+                }
+                for k in 1..=N {
+                    let (vkp, vkq) = (V[(k, p)], V[(k, q)]);
+                    V[(k, p)] = c * vkp - s * vkq;
+                    V[(k, q)] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    if !converged {
+        return Err(Error::TooManyIterations(MAX_SWEEPS));
+    }
+
+    let mut order: Vec<usize> = (1..=N).collect();
+    order.sort_by(|&a, &b| A[(a, a)].total_cmp(&A[(b, b)]));
+    let lambda = Mat::from_fn(|i, _| A[(order[i - 1], order[i - 1])]);
+    let V = Mat::from_fn(|i, j| V[(i, order[j - 1])]);
+    Ok((lambda, V))
+}
+
+#[test]
+fn symmetric_eigen_test() {
+    const N: usize = 6;
+    for _ in 0..SMALL_REPS {
+        let S = Symmetric::<N>::rand();
+        let (lambda, V) = symmetric_eigen(&S).unwrap();
+        for j in 1..=N {
+            assert!(lambda[j] >= lambda[j.max(2) - 1]);
+            let v = V.col(j);
+            assert_eq_mat!(&S * v, lambda[j] * v, 1e-4);
+        }
+        assert_eq_tol!(lambda.as_vec().iter().sum::<R>(), S.trace(), 1e-9);
+    }
+}
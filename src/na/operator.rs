@@ -0,0 +1,150 @@
+// Linear operators: anything that can multiply a vector, so that
+// iterative algorithms can run on matrices that are never formed, such
+// as stencils, products of factors and shifted inverses.
+
+use crate::prelude::*;
+
+#[allow(unused)]
+fn demo() {
+    let A = Mat::<3, 2>::rand();
+    LinearOperator::dimensions(&A);
+    A.apply_transpose(&Mat::rand());
+}
+
+/// A linear map from N-vectors to M-vectors, known only through its
+/// products with vectors.
+pub trait LinearOperator<const M: usize, const N: usize> {
+    /// Ax.
+    fn apply(&self, x: &Mat<N, 1>) -> Mat<M, 1>;
+
+    /// Aᵀy.
+    fn apply_transpose(&self, y: &Mat<M, 1>) -> Mat<N, 1>;
+
+    /// (rows, columns).
+    fn dimensions(&self) -> (usize, usize) {
+        (M, N)
+    }
+}
+
+impl<const M: usize, const N: usize> LinearOperator<M, N> for Mat<M, N> {
+    fn apply(&self, x: &Mat<N, 1>) -> Mat<M, 1> {
+        self * x
+    }
+
+    fn apply_transpose(&self, y: &Mat<M, 1>) -> Mat<N, 1> {
+        Mat::from_fn(|j, _| self.col(j).dot(y))
+    }
+}
+
+impl<const M: usize, const N: usize> LinearOperator<M, N> for Sparse<M, N> {
+    fn apply(&self, x: &Mat<N, 1>) -> Mat<M, 1> {
+        self * x
+    }
+
+    fn apply_transpose(&self, y: &Mat<M, 1>) -> Mat<N, 1> {
+        // Scatter each row, rather than forming the transpose.
+        let mut x = Mat::zero();
+        for i in 1..=M {
+            self.row(i).for_each(|(j, v)| x[j] += v * y[i]);
+        }
+        x
+    }
+}
+
+impl<const N: usize> LinearOperator<N, N> for Symmetric<N> {
+    fn apply(&self, x: &Mat<N, 1>) -> Mat<N, 1> {
+        self * x
+    }
+
+    fn apply_transpose(&self, y: &Mat<N, 1>) -> Mat<N, 1> {
+        self * y
+    }
+}
+
+impl<const N: usize> LinearOperator<N, N> for Diagonal<N> {
+    fn apply(&self, x: &Mat<N, 1>) -> Mat<N, 1> {
+        self * x
+    }
+
+    fn apply_transpose(&self, y: &Mat<N, 1>) -> Mat<N, 1> {
+        self * y
+    }
+}
+
+impl<const N: usize> LinearOperator<N, N> for UpperTriangular<N> {
+    fn apply(&self, x: &Mat<N, 1>) -> Mat<N, 1> {
+        self * x
+    }
+
+    fn apply_transpose(&self, y: &Mat<N, 1>) -> Mat<N, 1> {
+        &self.t() * y
+    }
+}
+
+impl<const N: usize> LinearOperator<N, N> for LowerTriangular<N> {
+    fn apply(&self, x: &Mat<N, 1>) -> Mat<N, 1> {
+        self * x
+    }
+
+    fn apply_transpose(&self, y: &Mat<N, 1>) -> Mat<N, 1> {
+        &self.t() * y
+    }
+}
+
+impl<const N: usize> LinearOperator<N, N> for Permutation<N> {
+    fn apply(&self, x: &Mat<N, 1>) -> Mat<N, 1> {
+        self * x
+    }
+
+    fn apply_transpose(&self, y: &Mat<N, 1>) -> Mat<N, 1> {
+        &self.t() * y
+    }
+}
+
+/// A pair of closures computing Ax and Aᵀy.
+impl<const M: usize, const N: usize, F, G> LinearOperator<M, N> for (F, G)
+where
+    F: Fn(&Mat<N, 1>) -> Mat<M, 1>,
+    G: Fn(&Mat<M, 1>) -> Mat<N, 1>,
+{
+    fn apply(&self, x: &Mat<N, 1>) -> Mat<M, 1> {
+        (self.0)(x)
+    }
+
+    fn apply_transpose(&self, y: &Mat<M, 1>) -> Mat<N, 1> {
+        (self.1)(y)
+    }
+}
+
+#[cfg(test)]
+fn check_operator<const M: usize, const N: usize>(
+    op: &impl LinearOperator<M, N>,
+    A: &Mat<M, N>,
+) {
+    let x = Mat::<N, 1>::rand();
+    let y = Mat::<M, 1>::rand();
+    assert_eq_mat!(op.apply(&x), A * &x, 1e-9);
+    assert_eq_mat!(op.apply_transpose(&y), A.t() * &y, 1e-9);
+    assert_eq!(op.dimensions(), (M, N));
+}
+
+#[test]
+fn operator_test() {
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<4, 3>::rand();
+        check_operator(&A, &A);
+        check_operator(&Sparse::from_mat(&A), &A);
+        let S = Symmetric::<4>::rand();
+        check_operator(&S, &S.to_mat());
+        let D = Diagonal::from(Mat::<4, 1>::rand());
+        check_operator(&D, &D.to_mat());
+        let U = UpperTriangular::from(Mat::<4, 4>::rand());
+        check_operator(&U, U.as_mat());
+        let L = U.t();
+        check_operator(&L, L.as_mat());
+        let P = Permutation::new([3, 1, 4, 2]).unwrap();
+        check_operator(&P, &P.to_mat());
+        let pair = (|x: &Mat<3, 1>| &A * x, |y: &Mat<4, 1>| A.t() * y);
+        check_operator(&pair, &A);
+    }
+}
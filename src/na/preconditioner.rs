@@ -0,0 +1,408 @@
+// Preconditioners for the Krylov solvers: cheap approximations M ≈ A
+// whose systems Mz = r are easy to solve.
+//
+// The incomplete factorizations work on sparse storage. A dense `Mat`
+// is factored on its nonzero pattern, through `Sparse::from_mat`.
+
+use crate::prelude::*;
+
+use std::collections::BTreeSet;
+use std::ops::Index;
+
+/// An approximation M of a matrix A, applied as z = M⁻¹r.
+pub trait Preconditioner<const N: usize> {
+    /// Solve Mz = r.
+    fn apply(&self, r: &Mat<N, 1>) -> Mat<N, 1>;
+}
+
+/// No preconditioning: M = I.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Identity;
+
+impl<const N: usize> Preconditioner<N> for Identity {
+    fn apply(&self, r: &Mat<N, 1>) -> Mat<N, 1> {
+        r.clone()
+    }
+}
+
+/// Jacobi (diagonal) preconditioning: M = diag(A).
+#[derive(Clone)]
+pub struct Jacobi<const N: usize> {
+    D: Diagonal<N>,
+}
+
+impl<const N: usize> Jacobi<N> {
+    /// Takes the diagonal of A, which MUST be nonzero.
+    pub fn new<A: Index<(usize, usize), Output = R>>(A: &A) -> Self {
+        Self { D: Mat::from_fn(|i, _| A[(i, i)]).into() }
+    }
+}
+
+impl<const N: usize> Preconditioner<N> for Jacobi<N> {
+    fn apply(&self, r: &Mat<N, 1>) -> Mat<N, 1> {
+        self.D.solve(r)
+    }
+}
+
+/// A sparse row, as (col, value) pairs in ascending column order.
+type Row = Vec<(usize, R)>;
+
+fn from_rows<const N: usize>(rows: &[Row]) -> Sparse<N, N> {
+    let entries: Vec<_> = (1..=N)
+        .flat_map(|i| rows[i - 1].iter().map(move |&(j, v)| (i, j, v)))
+        .collect();
+    Sparse::from_triplets(&entries)
+}
+
+/// Σ x_j y_j over the columns j < `end` stored in both rows.
+fn sparse_dot(x: &Row, y: &Row, end: usize) -> R {
+    let (mut a, mut b, mut dot) = (0, 0, 0.);
+    while a < x.len() && b < y.len() && x[a].0 < end && y[b].0 < end {
+        match x[a].0.cmp(&y[b].0) {
+            std::cmp::Ordering::Less => a += 1,
+            std::cmp::Ordering::Greater => b += 1,
+            std::cmp::Ordering::Equal => {
+                dot += x[a].1 * y[b].1;
+                a += 1;
+                b += 1;
+            }
+        }
+    }
+    dot
+}
+
+/// Incomplete Cholesky factorization with no fill-in, IC(0): M = LLᵀ,
+/// where L has the nonzero pattern of the lower triangle of A.
+#[derive(Clone)]
+pub struct IncompleteCholesky<const N: usize> {
+    L: Sparse<N, N>,
+}
+
+impl<const N: usize> IncompleteCholesky<N> {
+    /// Factor A, which MUST be symmetric (only its lower triangle is
+    /// read). Fails if a pivot is not positive, which can happen even
+    /// for some positive definite A; it can't for M-matrices such as
+    /// the Laplacians.
+    pub fn new(A: &Sparse<N, N>) -> Result<Self> {
+        let mut L: Vec<Row> = Vec::with_capacity(N);
+        for i in 1..=N {
+            let mut row: Row = A.row(i).filter(|&(j, _)| j <= i).collect();
+            for k in 0..row.len() {
+                let (j, a) = row[k];
+                // Entries left of column j are already computed.
+                if j < i {
+                    let s = a - sparse_dot(&row, &L[j - 1], j);
+                    row[k].1 = s / L[j - 1].last().unwrap().1;
+                } else {
+                    let s = a - sparse_dot(&row, &row, i);
+                    if s <= 0. {
+                        return Err(Error::NotPositiveDefinite);
+                    }
+                    row[k].1 = s.sqrt();
+                }
+            }
+            if row.last().map(|&(j, _)| j) != Some(i) {
+                return Err(Error::NotPositiveDefinite); // no diagonal.
+            }
+            L.push(row);
+        }
+        Ok(Self { L: from_rows(&L) })
+    }
+
+    /// Factor a dense A on its nonzero pattern.
+    pub fn from_mat(A: &Mat<N, N>) -> Result<Self> {
+        Self::new(&Sparse::from_mat(A))
+    }
+
+    /// The factor L.
+    pub fn factor(&self) -> &Sparse<N, N> {
+        &self.L
+    }
+
+    /// Solve LLᵀz = r.
+    pub fn solve(&self, r: &Mat<N, 1>) -> Mat<N, 1> {
+        let L = &self.L;
+        let mut z = r.clone();
+        for i in 1..=N {
+            let s: R =
+                L.row(i).filter(|&(j, _)| j < i).map(|(j, v)| v * z[j]).sum();
+            z[i] = (z[i] - s) / L[(i, i)];
+        }
+        // Lᵀ, column by column: each row of L scatters into z.
+        for i in (1..=N).rev() {
+            z[i] /= L[(i, i)];
+            for (j, v) in L.row(i).filter(|&(j, _)| j < i) {
+                z[j] -= v * z[i];
+            }
+        }
+        z
+    }
+}
+
+impl<const N: usize> Preconditioner<N> for IncompleteCholesky<N> {
+    fn apply(&self, r: &Mat<N, 1>) -> Mat<N, 1> {
+        self.solve(r)
+    }
+}
+
+/// Incomplete LU factorization: M = LU, with L unit lower triangular,
+/// and the entries of both kept sparse, either by pattern (ILU(0)) or
+/// by size (ILUT).
+#[derive(Clone)]
+pub struct IncompleteLu<const N: usize> {
+    /// L strictly below the diagonal, and U on and above it, as `na::lu`
+    /// lays them out.
+    LU: Sparse<N, N>,
+}
+
+impl<const N: usize> IncompleteLu<N> {
+    /// ILU(0): L and U together have the nonzero pattern of A.
+    ///
+    /// No pivoting is done, so fails with `Error::Breakdown` on a zero
+    /// (or missing) pivot. It can't for diagonally dominant A.
+    pub fn new(A: &Sparse<N, N>) -> Result<Self> {
+        let mut rows: Vec<Row> = (1..=N).map(|i| A.row(i).collect()).collect();
+        // Position of each column in the current row.
+        let mut position: Vec<Option<usize>> = vec![None; N + 1];
+        for i in 1..=N {
+            let (done, rest) = rows.split_at_mut(i - 1);
+            let row = &mut rest[0];
+            row.iter()
+                .enumerate()
+                .for_each(|(p, &(j, _))| position[j] = Some(p));
+            for p in 0..row.len() {
+                let k = row[p].0;
+                if k >= i {
+                    break;
+                }
+                let U_k = &done[k - 1];
+                row[p].1 /= pivot(U_k, k)?;
+                let l = row[p].1;
+                for &(j, u) in U_k.iter().filter(|&&(j, _)| j > k) {
+                    if let Some(q) = position[j] {
+                        row[q].1 -= l * u;
+                    }
+                }
+            }
+            pivot(row, i)?;
+            row.iter().for_each(|&(j, _)| position[j] = None);
+        }
+        Ok(Self { LU: from_rows(&rows) })
+    }
+
+    /// ILU(0) of a dense A, on its nonzero pattern.
+    pub fn from_mat(A: &Mat<N, N>) -> Result<Self> {
+        Self::new(&Sparse::from_mat(A))
+    }
+
+    /// ILUT(τ, p): fill-in is allowed anywhere, but after each row is
+    /// eliminated, entries smaller than `drop_tol` times the norm of that
+    /// row of A are dropped, and only the `fill` largest entries of its L
+    /// part and of its U part are kept (besides the diagonal).
+    ///
+    /// `drop_tol` = 0 and `fill` = N give the full LU factorization.
+    pub fn threshold(
+        A: &Sparse<N, N>,
+        drop_tol: R,
+        fill: usize,
+    ) -> Result<Self> {
+        let mut rows: Vec<Row> = Vec::with_capacity(N);
+        let mut w = vec![0.; N + 1];
+        for i in 1..=N {
+            let a_norm = A.row(i).map(|(_, v)| v * v).sum::<R>().sqrt();
+            let tol = drop_tol * a_norm;
+            let mut nonzero = BTreeSet::new();
+            for (j, v) in A.row(i) {
+                w[j] = v;
+                nonzero.insert(j);
+            }
+            // Eliminate left to right; fill-in joins the set as it goes.
+            let mut next = nonzero.range(..i).next().copied();
+            while let Some(k) = next {
+                let U_k = &rows[k - 1];
+                w[k] /= pivot(U_k, k)?;
+                if w[k].abs() < tol {
+                    w[k] = 0.;
+                } else {
+                    for &(j, u) in U_k.iter().filter(|&&(j, _)| j > k) {
+                        w[j] -= w[k] * u;
+                        nonzero.insert(j);
+                    }
+                }
+                next = nonzero.range(k + 1..i).next().copied();
+            }
+            let (mut L, mut U): (Row, Row) = (vec![], vec![]);
+            let mut diagonal = None;
+            for &j in &nonzero {
+                let v = std::mem::take(&mut w[j]);
+                if j == i {
+                    diagonal = Some((j, v));
+                } else if v != 0. && v.abs() >= tol {
+                    if j < i {
+                        L.push((j, v))
+                    } else {
+                        U.push((j, v))
+                    }
+                }
+            }
+            keep_largest(&mut L, fill);
+            keep_largest(&mut U, fill);
+            let mut row = L;
+            row.extend(diagonal);
+            row.extend(U);
+            pivot(&row, i)?;
+            rows.push(row);
+        }
+        Ok(Self { LU: from_rows(&rows) })
+    }
+
+    /// The factors L (with its unit diagonal) and U.
+    pub fn factors(&self) -> (Sparse<N, N>, Sparse<N, N>) {
+        let mut L = vec![];
+        let mut U = vec![];
+        for i in 1..=N {
+            L.push((i, i, 1.));
+            for (j, v) in self.LU.row(i) {
+                if j < i {
+                    L.push((i, j, v));
+                } else {
+                    U.push((i, j, v));
+                }
+            }
+        }
+        (Sparse::from_triplets(&L), Sparse::from_triplets(&U))
+    }
+
+    /// Solve LUz = r.
+    pub fn solve(&self, r: &Mat<N, 1>) -> Mat<N, 1> {
+        let LU = &self.LU;
+        let mut z = r.clone();
+        for i in 1..=N {
+            let s: R =
+                LU.row(i).filter(|&(j, _)| j < i).map(|(j, v)| v * z[j]).sum();
+            z[i] -= s;
+        }
+        for i in (1..=N).rev() {
+            let s: R =
+                LU.row(i).filter(|&(j, _)| j > i).map(|(j, v)| v * z[j]).sum();
+            z[i] = (z[i] - s) / LU[(i, i)];
+        }
+        z
+    }
+}
+
+impl<const N: usize> Preconditioner<N> for IncompleteLu<N> {
+    fn apply(&self, r: &Mat<N, 1>) -> Mat<N, 1> {
+        self.solve(r)
+    }
+}
+
+/// The nonzero diagonal entry (i, i) of a factored row.
+fn pivot(row: &Row, i: usize) -> Result<R> {
+    match row.iter().find(|&&(j, _)| j == i) {
+        Some(&(_, v)) if v != 0. => Ok(v),
+        _ => Err(Error::Breakdown),
+    }
+}
+
+/// Keep the `n` entries of largest magnitude, in column order.
+fn keep_largest(row: &mut Row, n: usize) {
+    if row.len() > n {
+        row.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+        row.truncate(n);
+        row.sort_by_key(|&(j, _)| j);
+    }
+}
+
+#[test]
+fn jacobi_preconditioner_test() {
+    let A = mat![4, 1; 1, -2];
+    let z = Jacobi::new(&A).apply(&mat![2; 3]);
+    assert_eq!(z.data, mat![0.5; -1.5].data);
+    let S = Sparse::from_mat(&A);
+    assert_eq!(Jacobi::new(&S).apply(&mat![2; 3]).data, z.data);
+}
+
+#[test]
+fn incomplete_cholesky_test() {
+    // With no zeros to preserve, IC(0) is the full Cholesky factor.
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<5, 5>::symmetric_positive_definite();
+        let ic = IncompleteCholesky::from_mat(&A).unwrap();
+        assert_eq_mat!(ic.factor().to_mat(), A.cholesky().into_mat(), 1e-9);
+        let r = Mat::<5, 1>::rand();
+        assert!((&A * &ic.apply(&r) - &r).l2_norm() < 1e-9 * r.l2_norm());
+    }
+    // Otherwise it keeps the zeros of A, and matches A on its pattern.
+    const N: usize = 16;
+    let A = crate::gallery::laplacian_2d_sparse::<4, N>();
+    let L = IncompleteCholesky::new(&A).unwrap().factor().to_mat();
+    let LLt = &L * L.t();
+    for i in 1..=N {
+        for j in 1..=i {
+            if A[(i, j)] == 0. {
+                assert_eq!(L[(i, j)], 0.);
+            } else {
+                assert!((LLt[(i, j)] - A[(i, j)]).abs() < 1e-12);
+            }
+        }
+    }
+    let indefinite = mat![1, 2; 2, 1];
+    let ic = IncompleteCholesky::from_mat(&indefinite);
+    assert!(matches!(ic, Err(Error::NotPositiveDefinite)));
+}
+
+#[test]
+fn incomplete_lu_test() {
+    // With no zeros to preserve, ILU(0) is the full LU factorization.
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<5, 5>::positive_definite();
+        let (L, U) = A.lu();
+        let (iL, iU) = IncompleteLu::from_mat(&A).unwrap().factors();
+        assert_eq_mat!(iL.to_mat(), L.into_mat(), 1e-9);
+        assert_eq_mat!(iU.to_mat(), U.into_mat(), 1e-9);
+    }
+    // Otherwise it keeps the zeros of A, and matches A on its pattern.
+    const N: usize = 25;
+    let mut A = crate::gallery::laplacian_2d::<5, N>();
+    (2..=N).for_each(|i| A[(i, i - 1)] *= 0.5); // nonsymmetric.
+    let A = Sparse::from_mat(&A);
+    let ilu = IncompleteLu::new(&A).unwrap();
+    let (L, U) = ilu.factors();
+    assert_eq!(L.nnz() + U.nnz() - N, A.nnz());
+    let LU = L.to_mat() * U.to_mat();
+    for i in 1..=N {
+        for (j, a) in A.row(i) {
+            assert!((LU[(i, j)] - a).abs() < 1e-12);
+        }
+    }
+    let r = Mat::<N, 1>::rand();
+    assert!((&LU * &ilu.apply(&r) - &r).l2_norm() < 1e-9 * r.l2_norm());
+
+    let zero_pivot = mat![0, 1; 1, 0];
+    let ilu = IncompleteLu::from_mat(&zero_pivot);
+    assert!(matches!(ilu, Err(Error::Breakdown)));
+}
+
+#[test]
+fn ilut_test() {
+    const N: usize = 25;
+    let mut A = crate::gallery::laplacian_2d::<5, N>();
+    (2..=N).for_each(|i| A[(i, i - 1)] *= 0.5);
+    let (L, U) = A.lu();
+    let A = Sparse::from_mat(&A);
+    // No dropping: the full LU factorization, fill-in and all.
+    let (tL, tU) = IncompleteLu::threshold(&A, 0., N).unwrap().factors();
+    assert_eq_mat!(tL.to_mat(), L.into_mat(), 1e-9);
+    assert_eq_mat!(tU.to_mat(), U.into_mat(), 1e-9);
+    // Dropping keeps at most `fill` entries on each side of the diagonal.
+    let ilut = IncompleteLu::threshold(&A, 1e-2, 3).unwrap();
+    let (tL, tU) = ilut.factors();
+    for i in 1..=N {
+        assert!(tL.row(i).count() <= 3 + 1);
+        assert!(tU.row(i).count() <= 3 + 1);
+    }
+    let ilu0 = IncompleteLu::new(&A).unwrap();
+    let (L0, U0) = ilu0.factors();
+    assert!(tL.nnz() + tU.nnz() > L0.nnz() + U0.nnz());
+}
@@ -1,10 +1,11 @@
+use crate::parallel;
 use crate::prelude::*;
 
 /// Compute the (reduced) QR factorization of the matrix A via the
 /// Gram-Schmidt process. Returns (Q, R) tuple.
 pub fn gram_schmidt<const M: usize, const N: usize>(
     A: &Mat<M, N>,
-) -> (Mat<M, N>, Mat<N, N>) {
+) -> (Mat<M, N>, UpperTriangular<N>) {
     let mut Q = A.clone();
 
     // Obtain the orthogonal matrix Q.
@@ -17,14 +18,14 @@ pub fn gram_schmidt<const M: usize, const N: usize>(
     }
 
     // Normalize the columns of Q.
-    (1..=N).for_each(|j| Q.col_mut(j).l2_normalize());
+    Q.for_each_col_mut(|_, q| q.l2_normalize());
 
     // Since A=QR and QᵀQ=I, we obtain R with QᵀA.
     //
     // Since we know that R is upper-triangular, we can skip the computation
     // for the lower-triangular portion.
     let x = |i, j| if i <= j { Q.col(i).dot(A.col(j)) } else { 0. };
-    let R = Mat::from_fn(x);
+    let R = Mat::from_fn(x).into();
 
     (Q, R)
 }
@@ -39,10 +40,17 @@ fn gram_schmidt_test() {
             let norm = Q.col(j).l2_norm();
             assert!(norm.abs_diff(1.) < 1e-9);
         }
-        assert_eq_mat!(A, Q * R);
+        assert_eq_mat!(A, Q * R.into_mat());
     }
 }
 
+/// Apply the Householder reflection (I - 2vvᵀ) to `x`, with v a unit
+/// vector.
+fn reflect<const M: usize>(v: &[R; M], x: &mut [R; M]) {
+    let s: R = 2. * v.iter().zip(x.iter()).map(|(v, x)| v * x).sum::<R>();
+    x.iter_mut().zip(v).for_each(|(x, v)| *x -= s * v);
+}
+
 /// Execute a QR decomposition via Householder reflections.
 /// This requires M ≥ N.
 pub fn householder<const M: usize, const N: usize>(
@@ -66,18 +74,21 @@ pub fn householder<const M: usize, const N: usize>(
         v[j] -= R[(j, j)];
 
         v.l2_normalize();
-        let vt = v.t();
-
-        // Rpply the HH transform on the remaining columns.
-        for i in j + 1..=N {
-            let sub = 2. * &v * (&vt * R.col(i));
-            let mut col = R.col_mut(i);
-            col -= sub;
-        }
-
-        // Q = Q * (I - 2vvᵀ);
-        let B = &Q * 2. * v * vt;
-        Q = Q - B;
+        let Qv = &Q * &v;
+        let (v, w) = (&v.data[0], &Qv.data[0]);
+
+        // Apply the HH transform on the remaining columns.
+        parallel::for_each_chunk(&mut R.data[j..], 4 * M, |_, cols| {
+            cols.iter_mut().for_each(|x| reflect(v, x))
+        });
+
+        // Q = Q * (I - 2vvᵀ) = Q - 2(Qv)vᵀ;
+        parallel::for_each_chunk(&mut Q.data, 2 * M, |c0, cols| {
+            for (c, q) in cols.iter_mut().enumerate() {
+                let s = 2. * v[c0 + c];
+                q.iter_mut().zip(w).for_each(|(q, w)| *q -= s * w);
+            }
+        });
     }
 
     (Q, R)
@@ -96,3 +107,24 @@ fn householder_refl_test() {
         assert_eq_mat!(Q * R, A);
     }
 }
+
+/// Reduced (thin) QR decomposition via Householder reflections: Q has
+/// orthonormal columns, and R is the square upper-triangular factor.
+/// This requires M ≥ N.
+pub fn householder_reduced<const M: usize, const N: usize>(
+    A: &Mat<M, N>,
+) -> (Mat<M, N>, UpperTriangular<N>) {
+    let (Q, R) = householder(A);
+    let Q1 = Mat::from_fn(|i, j| Q[(i, j)]);
+    let R1 = R.top_n_rows::<N>();
+    (Q1, R1.into())
+}
+
+#[test]
+fn householder_reduced_test() {
+    for _ in 0..REPS {
+        let A = Mat::<6, 4>::rand();
+        let (Q, R) = householder_reduced(&A);
+        assert_eq_mat!(Q * R.into_mat(), A);
+    }
+}
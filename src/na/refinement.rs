@@ -0,0 +1,227 @@
+// Iterative refinement: improve a computed solution of Ax = b by
+// solving for corrections Ad = r with the residual r = b - Ax, reusing
+// the factorization of A.
+//
+// In mixed precision, A is factored in f32 (twice the speed and half the
+// memory on real hardware) and the residuals are computed in f64, which
+// recovers f64 accuracy as long as κ(A) is well below 1/ε_f32 ≈ 10⁷.
+
+use crate::prelude::*;
+
+/// Error estimates after one refinement step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RefineStep {
+    /// Normwise backward error ‖b - Ax‖∞ / (‖A‖∞‖x‖∞ + ‖b‖∞) of the
+    /// iterate this step corrected: the smallest relative change to A
+    /// and b for which it is an exact solution.
+    pub backward_error: R,
+    /// Estimated relative forward error ‖x - x*‖∞ / ‖x*‖∞ of that
+    /// iterate: the size of its correction, ‖d‖∞ / ‖x + d‖∞.
+    pub forward_error: R,
+}
+
+/// A refined solution, with the error estimates of each step.
+#[derive(Debug, Clone)]
+pub struct Refinement<const N: usize> {
+    pub x: Mat<N, 1>,
+    pub steps: Vec<RefineStep>,
+    /// Whether the backward error reached the rounding level √N·ε. If
+    /// not, the refinement stagnated, typically because A is too ill
+    /// conditioned for the precision of the factorization.
+    pub converged: bool,
+}
+
+/// Refine a solution of Ax = b from the initial solve, for at most
+/// `max_steps` corrections. `solve` approximately solves Ad = r, with
+/// an existing factorization of A, in any precision.
+///
+/// Stops once the backward error is at the rounding level (as LAPACK's
+/// mixed precision solvers do), or the corrections no longer halve at
+/// each step.
+pub fn refine<const N: usize>(
+    A: &Mat<N, N>,
+    b: &Mat<N, 1>,
+    solve: impl Fn(&Mat<N, 1>) -> Mat<N, 1>,
+    max_steps: usize,
+) -> Refinement<N> {
+    let tol = (N as R).sqrt() * R::EPSILON;
+    let A_norm = A.linf_norm();
+    let mut x = solve(b);
+    let mut steps = vec![];
+    let mut last_correction = R::INFINITY;
+    for _ in 0..max_steps {
+        let r = b - A * &x;
+        let d = solve(&r);
+        // Both scales are 0 when b = 0, where x = 0 is exact.
+        let scale = A_norm * x.linf_norm() + b.linf_norm();
+        let backward_error = match scale {
+            0. => 0.,
+            scale => r.linf_norm() / scale,
+        };
+        x = &x + &d;
+        let correction = d.linf_norm();
+        let forward_error = match correction {
+            0. => 0.,
+            correction => correction / x.linf_norm(),
+        };
+        steps.push(RefineStep { backward_error, forward_error });
+        if backward_error <= tol {
+            return Refinement { x, steps, converged: true };
+        }
+        if correction.is_nan() || correction > last_correction / 2. {
+            break;
+        }
+        last_correction = correction;
+    }
+    Refinement { x, steps, converged: false }
+}
+
+/// LU factorization with partial pivoting, in f32.
+struct SingleLu<const N: usize> {
+    /// Column-major, in the layout of `na::lu`.
+    LU: Vec<f32>,
+    /// Row of the original matrix at each row of the factors.
+    perm: Vec<usize>,
+}
+
+impl<const N: usize> SingleLu<N> {
+    fn at(i: usize, j: usize) -> usize {
+        (i - 1) + (j - 1) * N
+    }
+
+    fn new(A: &Mat<N, N>) -> Self {
+        let mut LU: Vec<f32> =
+            A.data.iter().flatten().map(|&v| v as f32).collect();
+        let mut perm: Vec<usize> = (1..=N).collect();
+        for k in 1..N {
+            let p = (k..=N)
+                .max_by(|&a, &b| {
+                    let (a, b) = (LU[Self::at(a, k)], LU[Self::at(b, k)]);
+                    a.abs().total_cmp(&b.abs())
+                })
+                .unwrap_or(k);
+            if p != k {
+                (1..=N).for_each(|j| LU.swap(Self::at(k, j), Self::at(p, j)));
+                perm.swap(k - 1, p - 1);
+            }
+            let pivot = LU[Self::at(k, k)];
+            if pivot == 0. {
+                continue;
+            }
+            for i in k + 1..=N {
+                LU[Self::at(i, k)] /= pivot;
+            }
+            for j in k + 1..=N {
+                let ukj = LU[Self::at(k, j)];
+                for i in k + 1..=N {
+                    LU[Self::at(i, j)] -= LU[Self::at(i, k)] * ukj;
+                }
+            }
+        }
+        Self { LU, perm }
+    }
+
+    /// Solve Ax = b in f32.
+    fn solve(&self, b: &Mat<N, 1>) -> Mat<N, 1> {
+        let LU = &self.LU;
+        let mut x: Vec<f32> = self.perm.iter().map(|&i| b[i] as f32).collect();
+        for i in 1..=N {
+            for j in 1..i {
+                x[i - 1] -= LU[Self::at(i, j)] * x[j - 1];
+            }
+        }
+        for i in (1..=N).rev() {
+            for j in i + 1..=N {
+                x[i - 1] -= LU[Self::at(i, j)] * x[j - 1];
+            }
+            x[i - 1] /= LU[Self::at(i, i)];
+        }
+        Mat::from_fn(|i, _| x[i - 1] as R)
+    }
+}
+
+/// Solve Ax = b with an f32 LU factorization, refined with f64
+/// residuals, for at most `max_steps` corrections.
+///
+/// A MUST be non-singular.
+pub fn solve_mixed<const N: usize>(
+    A: &Mat<N, N>,
+    b: &Mat<N, 1>,
+    max_steps: usize,
+) -> Refinement<N> {
+    let lu = SingleLu::new(A);
+    refine(A, b, |r| lu.solve(r), max_steps)
+}
+
+#[cfg(test)]
+fn relative_error<const N: usize>(x: &Mat<N, 1>, expected: &Mat<N, 1>) -> R {
+    (x - expected).linf_norm() / expected.linf_norm()
+}
+
+#[test]
+fn mixed_precision_test() {
+    const N: usize = 8;
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::with_condition_number(1e4);
+        let x_true = Mat::<N, 1>::rand();
+        let b = &A * &x_true;
+        let single = SingleLu::new(&A).solve(&b);
+        let refined = solve_mixed(&A, &b, 10);
+        assert!(refined.converged);
+        // f32 alone gets about 7 - 4 digits, refinement all of them.
+        assert!(relative_error(&single, &x_true) > 1e-9);
+        assert!(relative_error(&refined.x, &x_true) < 1e-11);
+        let last = refined.steps.last().unwrap();
+        assert!(last.backward_error < 1e-15, "{last:?}");
+        let first = refined.steps[0];
+        assert!(first.backward_error > 1e3 * last.backward_error);
+        // The first correction estimates the error of the f32 solution.
+        let ratio = first.forward_error / relative_error(&single, &x_true);
+        assert!((0.5..2.).contains(&ratio), "{ratio}");
+    }
+}
+
+#[test]
+fn mixed_precision_ill_conditioned_test() {
+    // κ ≈ 10¹³ is far beyond f32.
+    const N: usize = 10;
+    let A = crate::gallery::hilbert::<N>();
+    let b = &A * &Mat::<N, 1>::from_fn(|_, _| 1.);
+    let refined = solve_mixed(&A, &b, 10);
+    assert!(!refined.converged);
+    assert!(refined.steps.len() < 10);
+}
+
+#[test]
+fn refine_zero_rhs_test() {
+    const N: usize = 4;
+    let A = Mat::<N, N>::with_condition_number(10.);
+    let b = Mat::<N, 1>::zero();
+    for refined in [solve_mixed(&A, &b, 5), refine(&A, &b, |r| r.clone(), 5)] {
+        assert!(refined.converged);
+        assert_eq!(refined.x.data, b.data);
+        let step = RefineStep { backward_error: 0., forward_error: 0. };
+        assert_eq!(refined.steps, [step]);
+    }
+}
+
+#[test]
+fn refine_test() {
+    const N: usize = 8;
+    for _ in 0..SMALL_REPS {
+        let A = Mat::<N, N>::symmetric_positive_definite();
+        let b = Mat::<N, 1>::rand();
+        let L = A.cholesky();
+        let cholesky = refine(&A, &b, |r| L.solve_transpose(&L.solve(r)), 5);
+        let (P, L, U) = A.lu_pivoted();
+        let lu = refine(&A, &b, |r| U.solve(&L.solve(&(&P * r))), 5);
+        let (Q, R) = A.qr_householder_reduced();
+        let qr = refine(&A, &b, |r| R.solve(&(Q.t() * r)), 5);
+        for refined in [cholesky, lu, qr] {
+            assert!(refined.converged);
+            let last = refined.steps.last().unwrap();
+            assert!(last.backward_error < 1e-15, "{last:?}");
+            assert!((&b - &A * &refined.x).linf_norm() < 1e-14);
+        }
+    }
+}
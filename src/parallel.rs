@@ -0,0 +1,125 @@
+// Opt-in multithreading for matrix kernels, on std scoped threads.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+static NUM_THREADS: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    /// Set by `with_num_threads`, for kernels called from this thread.
+    static SCOPED_NUM_THREADS: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Below this many flops per thread, spawning costs more than it saves.
+const MIN_WORK_PER_THREAD: usize = 1 << 16;
+
+/// Set the number of threads that matrix kernels may use. The default
+/// of 1 runs everything on the calling thread.
+pub fn set_num_threads(n: usize) {
+    NUM_THREADS.store(n.max(1), Ordering::Relaxed);
+}
+
+/// Run `f` with matrix kernels called from this thread using up to `n`
+/// threads, whatever `set_num_threads` says. Other threads are not
+/// affected.
+pub fn with_num_threads<T>(n: usize, f: impl FnOnce() -> T) -> T {
+    // Restores the previous setting even if `f` panics.
+    struct Restore(Option<usize>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED_NUM_THREADS.set(self.0);
+        }
+    }
+    let _restore = Restore(SCOPED_NUM_THREADS.replace(Some(n.max(1))));
+    f()
+}
+
+/// Number of threads that matrix kernels may use.
+pub fn num_threads() -> usize {
+    SCOPED_NUM_THREADS
+        .get()
+        .unwrap_or_else(|| NUM_THREADS.load(Ordering::Relaxed))
+}
+
+/// Number of threads `for_each_chunk` splits `n` columns over.
+fn threads_for(n: usize, work_per_col: usize) -> usize {
+    let enough_work = n * work_per_col / MIN_WORK_PER_THREAD;
+    num_threads().min(enough_work).min(n)
+}
+
+/// Runs `f(j, chunk)` over consecutive chunks of `cols`, where `j` is
+/// the (0-based) index of the first column in `chunk`.
+///
+/// `work_per_col` is the number of flops `f` spends per column. Chunks
+/// only go to separate threads if there is enough work to go around;
+/// either way every column is processed by the same code, so results
+/// do not depend on the thread count.
+pub fn for_each_chunk<T, F>(cols: &mut [T], work_per_col: usize, f: F)
+where
+    T: Send,
+    F: Fn(usize, &mut [T]) + Sync,
+{
+    let n = cols.len();
+    let threads = threads_for(n, work_per_col);
+    if threads <= 1 {
+        return f(0, cols);
+    }
+    let chunk = n.div_ceil(threads);
+    thread::scope(|s| {
+        for (c, cols) in cols.chunks_mut(chunk).enumerate() {
+            let f = &f;
+            s.spawn(move || f(c * chunk, cols));
+        }
+    });
+}
+
+#[test]
+fn parallel_matches_serial_test() {
+    use crate::prelude::*;
+
+    // Big enough for the Householder updates of QR to be split, with a
+    // stack to hold the matrices.
+    const N: usize = 256;
+    let test = || {
+        let A = Box::new(Mat::<N, N>::rand());
+        let B = Box::new(Mat::<N, N>::rand());
+
+        let AB = with_num_threads(1, || &*A * &*B);
+        let (Q, R) = with_num_threads(1, || A.qr_householder());
+
+        with_num_threads(4, || {
+            // The first Householder step updates N - 1 columns of R,
+            // and all N of Q.
+            assert!(threads_for(N - 1, 4 * N) > 1);
+            assert!(threads_for(N, 2 * N) > 1);
+            assert!(threads_for(N, 2 * N * N) > 1);
+            let par_AB = &*A * &*B;
+            let (par_Q, par_R) = A.qr_householder();
+            assert_eq!(AB.data, par_AB.data);
+            assert_eq!(Q.data, par_Q.data);
+            assert_eq!(R.data, par_R.data);
+        });
+    };
+    let test = thread::Builder::new().stack_size(64 << 20).spawn(test);
+    test.unwrap().join().unwrap();
+}
+
+#[test]
+fn for_each_chunk_test() {
+    use std::sync::Mutex;
+
+    let mut cols = vec![0; 10];
+    let starts = Mutex::new(vec![]);
+    with_num_threads(3, || {
+        for_each_chunk(&mut cols, MIN_WORK_PER_THREAD, |j, cols| {
+            starts.lock().unwrap().push(j);
+            cols.iter_mut().enumerate().for_each(|(i, c)| *c = j + i)
+        })
+    });
+    assert_eq!(cols, (0..10).collect::<Vec<_>>());
+    let mut starts = starts.into_inner().unwrap();
+    starts.sort();
+    assert_eq!(starts, [0, 4, 8]);
+    assert_eq!(num_threads(), 1);
+}
@@ -18,4 +18,28 @@ pub(crate) type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     TooManyIterations(usize),
     NoEigenvalues,
+    NotSymmetric,
+    /// A matrix or preconditioner that must be positive definite is not.
+    NotPositiveDefinite,
+    /// A zero pivot, or an iterative method dividing by zero before it
+    /// converged.
+    Breakdown,
+    NotAPermutation,
+    /// The matrix in a file or string is not of the requested size, or
+    /// a matrix is not of the size a format requires.
+    DimensionMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    /// No variable of this name in the file.
+    MissingVariable(String),
+    /// Malformed file or string contents.
+    Parse(String),
+    Io(std::io::Error),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
 }
@@ -0,0 +1,74 @@
+// Crate-wide source of randomness, seedable so that randomized tests can
+// be reproduced.
+//
+// Each thread (and so each test) has its own generator. It is seeded
+// from the `SEED` environment variable if set, and from entropy
+// otherwise. The `assert_eq_mat!` family prints the seed on failure, so
+// a failing test can be rerun with `SEED=<seed> cargo test <name>`.
+
+use rand::distributions::{Distribution, Standard};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use std::cell::RefCell;
+
+thread_local! {
+    static RNG: RefCell<(u64, StdRng)> = RefCell::new(seeded(initial_seed()));
+}
+
+fn initial_seed() -> u64 {
+    match std::env::var("SEED") {
+        Ok(seed) => seed.trim().parse().expect("SEED must be a u64"),
+        Err(_) => rand::random(),
+    }
+}
+
+fn seeded(seed: u64) -> (u64, StdRng) {
+    (seed, StdRng::seed_from_u64(seed))
+}
+
+/// Reseed this thread's generator.
+pub fn set_seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = seeded(seed));
+}
+
+/// The seed this thread's generator was last seeded with.
+pub fn seed() -> u64 {
+    RNG.with(|rng| rng.borrow().0)
+}
+
+/// Run `f` with this thread's generator.
+pub fn with_rng<T>(f: impl FnOnce(&mut StdRng) -> T) -> T {
+    RNG.with(|rng| f(&mut rng.borrow_mut().1))
+}
+
+/// A random value from this thread's generator, like `rand::random`.
+pub fn random<T>() -> T
+where
+    Standard: Distribution<T>,
+{
+    with_rng(|rng| Standard.sample(rng))
+}
+
+#[test]
+fn seed_test() {
+    use crate::prelude::*;
+
+    set_seed(42);
+    assert_eq!(seed(), 42);
+    let A = Mat::<3, 3>::rand();
+    let x: R = random();
+    set_seed(42);
+    assert_eq!(A.data, Mat::<3, 3>::rand().data);
+    assert_eq!(x, random::<R>());
+    assert_ne!(A.data, Mat::<3, 3>::rand().data);
+}
+
+#[test]
+#[should_panic(expected = "random seed: 7 (rerun with SEED=7)")]
+fn seed_on_failure_test() {
+    use crate::prelude::*;
+
+    set_seed(7);
+    assert_eq_mat!(Mat::<2, 2>::rand(), Mat::<2, 2>::rand());
+}